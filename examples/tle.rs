@@ -3,30 +3,36 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
-use tardis::geometry::Point;
+use std::rc::Rc;
+use chrono::Duration;
+use tardis::eclipse::EclipsePredictor;
+use tardis::geometry::FramedPoint;
+use tardis::passes::PassPredictor;
 use tardis::tle::TLE;
-use tardis::traits::{Frame, Framable, Observable};
+use tardis::time::Epoch;
+use tardis::traits::{Framable, Observable};
 use tardis::frames::{
     TEME,
-    ECEF
+    ECEF,
+    ITRF
 };
 
 use tardis::utils::{Coordinates, Observer};
+use tardis::Error;
 
-/*
-fn main() {
-    let p = Point::<TEME>::new(1.1, 1.2, 1.3);
-    let p2= p.change_frame(ECEF::new(Utc::now()));
+fn typed_frames(epoch: Epoch) -> Result<(), Error> {
+    let p = FramedPoint::new(1000.0, 1000.0, 1000.0, TEME::new(epoch));
+    let p2 = p.to_frame(ECEF::new(epoch))?;
     println!("{} {}", p, p2);
 
-    let teme = TEME::new(DateTime::from_utc(NaiveDate::from_ymd(2021, 11, 15).and_hms(12, 0, 0), Utc));
-    println!("{:?}", teme.to_gcrf([1000.0, 1000.0, 1000.0]));
-    println!("{:?}", teme.from_gcrf(teme.to_gcrf([1000.0, 1000.0, 1000.0])));
+    // p + p2 would not compile: the points are not in the same frame
+    let back = p2.to_frame(TEME::new(epoch))?;
+    println!("Round trip error: {} km", (back - p).length());
+
+    Ok(())
 }
-*/
 
-fn main() -> Result<(), String> {
+fn main() -> Result<(), Error> {
     let tle_lines = vec![
         "ISS (ZARYA)                                                          ".as_bytes(),
         "1 25544U 98067A   21288.70144628  .00006635  00000-0  12985-3 0  9991".as_bytes(),
@@ -43,17 +49,13 @@ fn main() -> Result<(), String> {
         "2 25544  51.6442 328.9484 0004731 186.1225 318.0089 15.48559922311590".as_bytes(),
     ];
 
-    let mont_royal_coordinates = Coordinates::new(
+    let mont_royal_coordinates = Coordinates::with_altitude(
         45.508888,
         -73.561668,
+        0.233,
     );
 
-    let satellite = match TLE::from_lines(&tle_lines[1], &tle_lines[2], &tle_lines[0]) {
-        Ok(s) => s,
-        Err(err) => {
-            return Err("Cannot parse TLE: ".to_owned() + &err);
-        }
-    };
+    let satellite = TLE::from_lines(tle_lines[1], tle_lines[2], tle_lines[0])?;
 
     println!("TLE: {}", satellite);
 
@@ -61,12 +63,29 @@ fn main() -> Result<(), String> {
 
     let observer = Observer::new(mont_royal_coordinates);
 
-    let obs = match satellite.observation(&observer) {
-        Ok(o) => o,
-        Err(e) => return Err(e),
-    };
+    let mut obs = satellite.observation(&observer)?;
 
     println!("Current observation: {}", obs);
 
+    obs.state.change_frame(Rc::new(ITRF::new(obs.time)))?;
+    println!("Earth-fixed state: {}", obs.state);
+
+    typed_frames(obs.time)?;
+
+    let passes = PassPredictor::new(&satellite, &observer)
+        .with_min_elevation(10.0)
+        .passes(obs.time, obs.time + Duration::days(1))?;
+
+    for pass in passes {
+        println!("Pass culminating at {} ({}° elevation)", pass.culmination.time, pass.max_elevation().degrees());
+    }
+
+    let events = EclipsePredictor::new(&satellite)
+        .events(obs.time, obs.time + Duration::hours(3))?;
+
+    for event in events {
+        println!("{:?} at {}", event.kind, event.time);
+    }
+
     Ok(())
 }
@@ -3,12 +3,11 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-use std::env;
-use std::path::PathBuf;
-use cc;
-use bindgen;
+#[cfg(all(feature = "c-backend", not(feature = "rust-backend")))]
+fn build_c_backend() {
+    use std::env;
+    use std::path::PathBuf;
 
-fn main() {
     // Tell Cargo that if the given file changes, to rerun this build script.
     println!("cargo:rerun-if-changed=src/c/sgp4.c");
     println!("cargo:rerun-if-changed=src/c/SGP4.h");
@@ -29,3 +28,9 @@ fn main() {
         .write_to_file(out_path.join("c_sgp4.rs"))
         .expect("Failed to write bindings");
 }
+
+fn main() {
+    // The Rust backend does not need anything to be built
+    #[cfg(all(feature = "c-backend", not(feature = "rust-backend")))]
+    build_c_backend();
+}
@@ -0,0 +1,76 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Safe entry points to the selected SGP4 implementation.
+ *
+ * The C library is used by default (`c-backend` feature). The `rust-backend`
+ * feature replaces it by a port in safe Rust which does not need a C compiler
+ * nor libclang at build time. When both features are enabled, the Rust
+ * implementation is used.
+ */
+
+use crate::ElsetRec;
+
+#[cfg(feature = "rust-backend")]
+use crate::propagator;
+
+/// Return an empty record, ready to receive the elements
+#[cfg(feature = "rust-backend")]
+pub(crate) fn new_record() -> ElsetRec {
+    ElsetRec::default()
+}
+
+#[cfg(not(feature = "rust-backend"))]
+pub(crate) fn new_record() -> ElsetRec {
+    // The C structure only contains numbers, all zeros is a valid value.
+    unsafe { std::mem::zeroed() }
+}
+
+/// Initialize the record for the given operation mode ('a' or 'i').
+/// Return false if the elements are invalid, the error is then set in the record.
+#[cfg(feature = "rust-backend")]
+pub(crate) fn init(mode: char, rec: &mut ElsetRec) -> bool {
+    propagator::sgp4init(mode as u8, rec)
+}
+
+#[cfg(not(feature = "rust-backend"))]
+pub(crate) fn init(mode: char, rec: &mut ElsetRec) -> bool {
+    unsafe { crate::sgp4init(mode as std::os::raw::c_char, rec as *mut ElsetRec) != 0 }
+}
+
+/// Propagate the record `minutes` after its epoch.
+/// Return false on error, the error is then set in the record.
+#[cfg(feature = "rust-backend")]
+pub(crate) fn propagate(rec: &mut ElsetRec, minutes: f64, r: &mut [f64; 3], v: &mut [f64; 3]) -> bool {
+    propagator::sgp4(rec, minutes, r, v)
+}
+
+#[cfg(not(feature = "rust-backend"))]
+pub(crate) fn propagate(rec: &mut ElsetRec, minutes: f64, r: &mut [f64; 3], v: &mut [f64; 3]) -> bool {
+    unsafe { crate::sgp4(rec as *mut ElsetRec, minutes, r.as_mut_ptr(), v.as_mut_ptr()) != 0 }
+}
+
+/// Compute the Julian day of the given date, as a (day, fraction of day) pair
+#[cfg(feature = "rust-backend")]
+pub(crate) fn jday(year: i32, mon: i32, day: i32, hr: i32, minute: i32, sec: f64) -> (f64, f64) {
+    let mut jd = 0.0;
+    let mut jdfrac = 0.0;
+
+    propagator::jday(year, mon, day, hr, minute, sec, &mut jd, &mut jdfrac);
+
+    (jd, jdfrac)
+}
+
+#[cfg(not(feature = "rust-backend"))]
+pub(crate) fn jday(year: i32, mon: i32, day: i32, hr: i32, minute: i32, sec: f64) -> (f64, f64) {
+    let mut jd = 0.0;
+    let mut jdfrac = 0.0;
+
+    unsafe {
+        crate::jday(year, mon, day, hr, minute, sec, &mut jd as *mut f64, &mut jdfrac as *mut f64);
+    }
+
+    (jd, jdfrac)
+}
@@ -7,6 +7,16 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+#[cfg(not(any(feature = "c-backend", feature = "rust-backend")))]
+compile_error!("Either the `c-backend` or the `rust-backend` feature must be enabled");
+
+#[cfg(all(feature = "c-backend", not(feature = "rust-backend")))]
 include!(concat!(env!("OUT_DIR"), "/c_sgp4.rs"));
 
-pub mod sgp4;
\ No newline at end of file
+#[cfg(feature = "rust-backend")]
+mod propagator;
+#[cfg(feature = "rust-backend")]
+pub use propagator::ElsetRec;
+
+mod backend;
+pub mod sgp4;
@@ -0,0 +1,1239 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* This is a safe Rust port of the C SGP4 implementation found in src/c/sgp4.c
+ * (Vallado's "SGP4 Version 2016-03-09").
+ *
+ * The structure of the original code has been kept on purpose: the procedures
+ * have the same names, use the same intermediate variables and are evaluated in
+ * the same order so that both backends produce identical results.
+ * Comments have been stripped, see the C file for the detailed documentation of
+ * each procedure.
+ */
+
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::excessive_precision)]
+
+use std::f64::consts::PI;
+
+const TWOPI: f64 = 2.0 * PI;
+const DEG2RAD: f64 = PI / 180.0;
+
+const WGS72OLD: i32 = 1;
+const WGS72: i32 = 2;
+
+///
+/// Rust counterpart of the C `ElsetRec` structure.
+///
+/// Only the fields that are used by the algorithm are kept. Characters are
+/// stored as ASCII bytes ('a'/'i' for the operation mode, 'y'/'n' for init and
+/// 'n'/'d' for the method).
+#[derive(Default, Clone)]
+pub struct ElsetRec {
+    pub whichconst: i32,
+    pub error: i32,
+    pub operationmode: u8,
+    pub init: u8,
+    pub method: u8,
+    pub a: f64,
+    pub altp: f64,
+    pub alta: f64,
+    pub jdsatepoch: f64,
+    pub jdsatepochF: f64,
+    pub bstar: f64,
+    pub inclo: f64,
+    pub nodeo: f64,
+    pub ecco: f64,
+    pub argpo: f64,
+    pub mo: f64,
+    pub no_kozai: f64,
+
+    pub no_unkozai: f64,
+
+    pub am: f64,
+    pub em: f64,
+    pub im: f64,
+    pub Om: f64,
+    pub om: f64,
+    pub mm: f64,
+    pub nm: f64,
+    pub t: f64,
+
+    pub tumin: f64,
+    pub mu: f64,
+    pub radiusearthkm: f64,
+    pub xke: f64,
+    pub j2: f64,
+    pub j3: f64,
+    pub j4: f64,
+    pub j3oj2: f64,
+
+    pub ep: f64,
+    pub inclp: f64,
+    pub nodep: f64,
+    pub argpp: f64,
+    pub mp: f64,
+
+    pub isimp: i32,
+    pub aycof: f64,
+    pub con41: f64,
+    pub cc1: f64,
+    pub cc4: f64,
+    pub cc5: f64,
+    pub d2: f64,
+    pub d3: f64,
+    pub d4: f64,
+    pub delmo: f64,
+    pub eta: f64,
+    pub argpdot: f64,
+    pub omgcof: f64,
+    pub sinmao: f64,
+    pub t2cof: f64,
+    pub t3cof: f64,
+    pub t4cof: f64,
+    pub t5cof: f64,
+    pub x1mth2: f64,
+    pub x7thm1: f64,
+    pub mdot: f64,
+    pub nodedot: f64,
+    pub xlcof: f64,
+    pub xmcof: f64,
+    pub nodecf: f64,
+
+    pub irez: i32,
+    pub d2201: f64,
+    pub d2211: f64,
+    pub d3210: f64,
+    pub d3222: f64,
+    pub d4410: f64,
+    pub d4422: f64,
+    pub d5220: f64,
+    pub d5232: f64,
+    pub d5421: f64,
+    pub d5433: f64,
+    pub dedt: f64,
+    pub del1: f64,
+    pub del2: f64,
+    pub del3: f64,
+    pub didt: f64,
+    pub dmdt: f64,
+    pub dnodt: f64,
+    pub domdt: f64,
+    pub e3: f64,
+    pub ee2: f64,
+    pub peo: f64,
+    pub pgho: f64,
+    pub pho: f64,
+    pub pinco: f64,
+    pub plo: f64,
+    pub se2: f64,
+    pub se3: f64,
+    pub sgh2: f64,
+    pub sgh3: f64,
+    pub sgh4: f64,
+    pub sh2: f64,
+    pub sh3: f64,
+    pub si2: f64,
+    pub si3: f64,
+    pub sl2: f64,
+    pub sl3: f64,
+    pub sl4: f64,
+    pub gsto: f64,
+    pub xfact: f64,
+    pub xgh2: f64,
+    pub xgh3: f64,
+    pub xgh4: f64,
+    pub xh2: f64,
+    pub xh3: f64,
+    pub xi2: f64,
+    pub xi3: f64,
+    pub xl2: f64,
+    pub xl3: f64,
+    pub xl4: f64,
+    pub xlamo: f64,
+    pub zmol: f64,
+    pub zmos: f64,
+    pub atime: f64,
+    pub xli: f64,
+    pub xni: f64,
+    pub snodm: f64,
+    pub cnodm: f64,
+    pub sinim: f64,
+    pub cosim: f64,
+    pub sinomm: f64,
+    pub cosomm: f64,
+    pub day: f64,
+    pub emsq: f64,
+    pub gam: f64,
+    pub rtemsq: f64,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+    pub s4: f64,
+    pub s5: f64,
+    pub s6: f64,
+    pub s7: f64,
+    pub ss1: f64,
+    pub ss2: f64,
+    pub ss3: f64,
+    pub ss4: f64,
+    pub ss5: f64,
+    pub ss6: f64,
+    pub ss7: f64,
+    pub sz1: f64,
+    pub sz2: f64,
+    pub sz3: f64,
+    pub sz11: f64,
+    pub sz12: f64,
+    pub sz13: f64,
+    pub sz21: f64,
+    pub sz22: f64,
+    pub sz23: f64,
+    pub sz31: f64,
+    pub sz32: f64,
+    pub sz33: f64,
+    pub z1: f64,
+    pub z2: f64,
+    pub z3: f64,
+    pub z11: f64,
+    pub z12: f64,
+    pub z13: f64,
+    pub z21: f64,
+    pub z22: f64,
+    pub z23: f64,
+    pub z31: f64,
+    pub z32: f64,
+    pub z33: f64,
+    pub argpm: f64,
+    pub inclm: f64,
+    pub nodem: f64,
+    pub dndt: f64,
+    pub eccsq: f64,
+
+    pub ainv: f64,
+    pub ao: f64,
+    pub con42: f64,
+    pub cosio: f64,
+    pub cosio2: f64,
+    pub omeosq: f64,
+    pub posq: f64,
+    pub rp: f64,
+    pub rteosq: f64,
+    pub sinio: f64,
+}
+
+/// Deep space long period periodic contributions to the mean elements.
+fn dpper(rec: &mut ElsetRec, init: u8, opsmode: u8) {
+    let zns = 1.19459e-5;
+    let zes = 0.01675;
+    let znl = 1.5835218e-4;
+    let zel = 0.05490;
+
+    let t = rec.t;
+
+    let mut zm = rec.zmos + zns * t;
+    if init == b'y' {
+        zm = rec.zmos;
+    }
+    let mut zf = zm + 2.0 * zes * zm.sin();
+    let mut sinzf = zf.sin();
+    let mut f2 = 0.5 * sinzf * sinzf - 0.25;
+    let mut f3 = -0.5 * sinzf * zf.cos();
+    let ses = rec.se2 * f2 + rec.se3 * f3;
+    let sis = rec.si2 * f2 + rec.si3 * f3;
+    let sls = rec.sl2 * f2 + rec.sl3 * f3 + rec.sl4 * sinzf;
+    let sghs = rec.sgh2 * f2 + rec.sgh3 * f3 + rec.sgh4 * sinzf;
+    let shs = rec.sh2 * f2 + rec.sh3 * f3;
+    zm = rec.zmol + znl * t;
+    if init == b'y' {
+        zm = rec.zmol;
+    }
+    zf = zm + 2.0 * zel * zm.sin();
+    sinzf = zf.sin();
+    f2 = 0.5 * sinzf * sinzf - 0.25;
+    f3 = -0.5 * sinzf * zf.cos();
+    let sel = rec.ee2 * f2 + rec.e3 * f3;
+    let sil = rec.xi2 * f2 + rec.xi3 * f3;
+    let sll = rec.xl2 * f2 + rec.xl3 * f3 + rec.xl4 * sinzf;
+    let sghl = rec.xgh2 * f2 + rec.xgh3 * f3 + rec.xgh4 * sinzf;
+    let shll = rec.xh2 * f2 + rec.xh3 * f3;
+    let mut pe = ses + sel;
+    let mut pinc = sis + sil;
+    let mut pl = sls + sll;
+    let mut pgh = sghs + sghl;
+    let mut ph = shs + shll;
+
+    if init == b'n' {
+        pe -= rec.peo;
+        pinc -= rec.pinco;
+        pl -= rec.plo;
+        pgh -= rec.pgho;
+        ph -= rec.pho;
+        rec.inclp += pinc;
+        rec.ep += pe;
+        let sinip = rec.inclp.sin();
+        let cosip = rec.inclp.cos();
+
+        if rec.inclp >= 0.2 {
+            ph /= sinip;
+            pgh -= cosip * ph;
+            rec.argpp += pgh;
+            rec.nodep += ph;
+            rec.mp += pl;
+        } else {
+            let sinop = rec.nodep.sin();
+            let cosop = rec.nodep.cos();
+            let mut alfdp = sinip * sinop;
+            let mut betdp = sinip * cosop;
+            let dalf = ph * cosop + pinc * cosip * sinop;
+            let dbet = -ph * sinop + pinc * cosip * cosop;
+            alfdp += dalf;
+            betdp += dbet;
+            rec.nodep %= TWOPI;
+            if rec.nodep < 0.0 && opsmode == b'a' {
+                rec.nodep += TWOPI;
+            }
+            let mut xls = rec.mp + rec.argpp + cosip * rec.nodep;
+            let dls = pl + pgh - pinc * rec.nodep * sinip;
+            xls += dls;
+            xls %= TWOPI;
+            let xnoh = rec.nodep;
+            rec.nodep = alfdp.atan2(betdp);
+            if rec.nodep < 0.0 && opsmode == b'a' {
+                rec.nodep += TWOPI;
+            }
+            if (xnoh - rec.nodep).abs() > PI {
+                if rec.nodep < xnoh {
+                    rec.nodep += TWOPI;
+                } else {
+                    rec.nodep -= TWOPI;
+                }
+            }
+            rec.mp += pl;
+            rec.argpp = xls - rec.mp - cosip * rec.nodep;
+        }
+    }
+}
+
+/// Deep space common items used by both the secular and periodics subroutines.
+fn dscom(epoch: f64, ep: f64, argpp: f64, tc: f64, inclp: f64, nodep: f64, np: f64, rec: &mut ElsetRec) {
+    let zes = 0.01675;
+    let zel = 0.05490;
+    let c1ss = 2.9864797e-6;
+    let c1l = 4.7968065e-7;
+    let zsinis = 0.39785416;
+    let zcosis = 0.91744867;
+    let zcosgs = 0.1945905;
+    let zsings = -0.98088458;
+
+    rec.nm = np;
+    rec.em = ep;
+    rec.snodm = nodep.sin();
+    rec.cnodm = nodep.cos();
+    rec.sinomm = argpp.sin();
+    rec.cosomm = argpp.cos();
+    rec.sinim = inclp.sin();
+    rec.cosim = inclp.cos();
+    rec.emsq = rec.em * rec.em;
+    let betasq = 1.0 - rec.emsq;
+    rec.rtemsq = betasq.sqrt();
+
+    rec.peo = 0.0;
+    rec.pinco = 0.0;
+    rec.plo = 0.0;
+    rec.pgho = 0.0;
+    rec.pho = 0.0;
+    rec.day = epoch + 18261.5 + tc / 1440.0;
+    let xnodce = (4.5236020 - 9.2422029e-4 * rec.day) % TWOPI;
+    let stem = xnodce.sin();
+    let ctem = xnodce.cos();
+    let zcosil = 0.91375164 - 0.03568096 * ctem;
+    let zsinil = (1.0 - zcosil * zcosil).sqrt();
+    let zsinhl = 0.089683511 * stem / zsinil;
+    let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+    rec.gam = 5.8351514 + 0.0019443680 * rec.day;
+    let mut zx = 0.39785416 * stem / zsinil;
+    let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+    zx = zx.atan2(zy);
+    zx = rec.gam + zx - xnodce;
+    let zcosgl = zx.cos();
+    let zsingl = zx.sin();
+
+    let mut zcosg = zcosgs;
+    let mut zsing = zsings;
+    let mut zcosi = zcosis;
+    let mut zsini = zsinis;
+    let mut zcosh = rec.cnodm;
+    let mut zsinh = rec.snodm;
+    let mut cc = c1ss;
+    let xnoi = 1.0 / rec.nm;
+
+    for lsflg in 1..=2 {
+        let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+        let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+        let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+        let a8 = zsing * zsini;
+        let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+        let a10 = zcosg * zsini;
+        let a2 = rec.cosim * a7 + rec.sinim * a8;
+        let a4 = rec.cosim * a9 + rec.sinim * a10;
+        let a5 = -rec.sinim * a7 + rec.cosim * a8;
+        let a6 = -rec.sinim * a9 + rec.cosim * a10;
+
+        let x1 = a1 * rec.cosomm + a2 * rec.sinomm;
+        let x2 = a3 * rec.cosomm + a4 * rec.sinomm;
+        let x3 = -a1 * rec.sinomm + a2 * rec.cosomm;
+        let x4 = -a3 * rec.sinomm + a4 * rec.cosomm;
+        let x5 = a5 * rec.sinomm;
+        let x6 = a6 * rec.sinomm;
+        let x7 = a5 * rec.cosomm;
+        let x8 = a6 * rec.cosomm;
+
+        rec.z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+        rec.z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+        rec.z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+        rec.z1 = 3.0 * (a1 * a1 + a2 * a2) + rec.z31 * rec.emsq;
+        rec.z2 = 6.0 * (a1 * a3 + a2 * a4) + rec.z32 * rec.emsq;
+        rec.z3 = 3.0 * (a3 * a3 + a4 * a4) + rec.z33 * rec.emsq;
+        rec.z11 = -6.0 * a1 * a5 + rec.emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+        rec.z12 = -6.0 * (a1 * a6 + a3 * a5) + rec.emsq *
+            (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+        rec.z13 = -6.0 * a3 * a6 + rec.emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+        rec.z21 = 6.0 * a2 * a5 + rec.emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+        rec.z22 = 6.0 * (a4 * a5 + a2 * a6) + rec.emsq *
+            (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+        rec.z23 = 6.0 * a4 * a6 + rec.emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+        rec.z1 = rec.z1 + rec.z1 + betasq * rec.z31;
+        rec.z2 = rec.z2 + rec.z2 + betasq * rec.z32;
+        rec.z3 = rec.z3 + rec.z3 + betasq * rec.z33;
+        rec.s3 = cc * xnoi;
+        rec.s2 = -0.5 * rec.s3 / rec.rtemsq;
+        rec.s4 = rec.s3 * rec.rtemsq;
+        rec.s1 = -15.0 * rec.em * rec.s4;
+        rec.s5 = x1 * x3 + x2 * x4;
+        rec.s6 = x2 * x3 + x1 * x4;
+        rec.s7 = x2 * x4 - x1 * x3;
+
+        if lsflg == 1 {
+            rec.ss1 = rec.s1;
+            rec.ss2 = rec.s2;
+            rec.ss3 = rec.s3;
+            rec.ss4 = rec.s4;
+            rec.ss5 = rec.s5;
+            rec.ss6 = rec.s6;
+            rec.ss7 = rec.s7;
+            rec.sz1 = rec.z1;
+            rec.sz2 = rec.z2;
+            rec.sz3 = rec.z3;
+            rec.sz11 = rec.z11;
+            rec.sz12 = rec.z12;
+            rec.sz13 = rec.z13;
+            rec.sz21 = rec.z21;
+            rec.sz22 = rec.z22;
+            rec.sz23 = rec.z23;
+            rec.sz31 = rec.z31;
+            rec.sz32 = rec.z32;
+            rec.sz33 = rec.z33;
+            zcosg = zcosgl;
+            zsing = zsingl;
+            zcosi = zcosil;
+            zsini = zsinil;
+            zcosh = zcoshl * rec.cnodm + zsinhl * rec.snodm;
+            zsinh = rec.snodm * zcoshl - rec.cnodm * zsinhl;
+            cc = c1l;
+        }
+    }
+
+    rec.zmol = (4.7199672 + 0.22997150 * rec.day - rec.gam) % TWOPI;
+    rec.zmos = (6.2565837 + 0.017201977 * rec.day) % TWOPI;
+
+    rec.se2 = 2.0 * rec.ss1 * rec.ss6;
+    rec.se3 = 2.0 * rec.ss1 * rec.ss7;
+    rec.si2 = 2.0 * rec.ss2 * rec.sz12;
+    rec.si3 = 2.0 * rec.ss2 * (rec.sz13 - rec.sz11);
+    rec.sl2 = -2.0 * rec.ss3 * rec.sz2;
+    rec.sl3 = -2.0 * rec.ss3 * (rec.sz3 - rec.sz1);
+    rec.sl4 = -2.0 * rec.ss3 * (-21.0 - 9.0 * rec.emsq) * zes;
+    rec.sgh2 = 2.0 * rec.ss4 * rec.sz32;
+    rec.sgh3 = 2.0 * rec.ss4 * (rec.sz33 - rec.sz31);
+    rec.sgh4 = -18.0 * rec.ss4 * zes;
+    rec.sh2 = -2.0 * rec.ss2 * rec.sz22;
+    rec.sh3 = -2.0 * rec.ss2 * (rec.sz23 - rec.sz21);
+
+    rec.ee2 = 2.0 * rec.s1 * rec.s6;
+    rec.e3 = 2.0 * rec.s1 * rec.s7;
+    rec.xi2 = 2.0 * rec.s2 * rec.z12;
+    rec.xi3 = 2.0 * rec.s2 * (rec.z13 - rec.z11);
+    rec.xl2 = -2.0 * rec.s3 * rec.z2;
+    rec.xl3 = -2.0 * rec.s3 * (rec.z3 - rec.z1);
+    rec.xl4 = -2.0 * rec.s3 * (-21.0 - 9.0 * rec.emsq) * zel;
+    rec.xgh2 = 2.0 * rec.s4 * rec.z32;
+    rec.xgh3 = 2.0 * rec.s4 * (rec.z33 - rec.z31);
+    rec.xgh4 = -18.0 * rec.s4 * zel;
+    rec.xh2 = -2.0 * rec.s2 * rec.z22;
+    rec.xh3 = -2.0 * rec.s2 * (rec.z23 - rec.z21);
+}
+
+/// Deep space contributions to the mean motion dot due to geopotential resonance
+/// with half day and one day orbits.
+fn dsinit(tc: f64, xpidot: f64, rec: &mut ElsetRec) {
+    let q22 = 1.7891679e-6;
+    let q31 = 2.1460748e-6;
+    let q33 = 2.2123015e-7;
+    let root22 = 1.7891679e-6;
+    let root44 = 7.3636953e-9;
+    let root54 = 2.1765803e-9;
+    let rptim = 4.37526908801129966e-3;
+    let root32 = 3.7393792e-7;
+    let root52 = 1.1428639e-7;
+    let x2o3 = 2.0 / 3.0;
+    let znl = 1.5835218e-4;
+    let zns = 1.19459e-5;
+
+    rec.irez = 0;
+    if rec.nm < 0.0052359877 && rec.nm > 0.0034906585 {
+        rec.irez = 1;
+    }
+    if rec.nm >= 8.26e-3 && rec.nm <= 9.24e-3 && rec.em >= 0.5 {
+        rec.irez = 2;
+    }
+
+    let ses = rec.ss1 * zns * rec.ss5;
+    let sis = rec.ss2 * zns * (rec.sz11 + rec.sz13);
+    let sls = -zns * rec.ss3 * (rec.sz1 + rec.sz3 - 14.0 - 6.0 * rec.emsq);
+    let sghs = rec.ss4 * zns * (rec.sz31 + rec.sz33 - 6.0);
+    let mut shs = -zns * rec.ss2 * (rec.sz21 + rec.sz23);
+    if rec.inclm < 5.2359877e-2 || rec.inclm > PI - 5.2359877e-2 {
+        shs = 0.0;
+    }
+    if rec.sinim != 0.0 {
+        shs /= rec.sinim;
+    }
+    let sgs = sghs - rec.cosim * shs;
+
+    rec.dedt = ses + rec.s1 * znl * rec.s5;
+    rec.didt = sis + rec.s2 * znl * (rec.z11 + rec.z13);
+    rec.dmdt = sls - znl * rec.s3 * (rec.z1 + rec.z3 - 14.0 - 6.0 * rec.emsq);
+    let sghl = rec.s4 * znl * (rec.z31 + rec.z33 - 6.0);
+    let mut shll = -znl * rec.s2 * (rec.z21 + rec.z23);
+    if rec.inclm < 5.2359877e-2 || rec.inclm > PI - 5.2359877e-2 {
+        shll = 0.0;
+    }
+    rec.domdt = sgs + sghl;
+    rec.dnodt = shs;
+    if rec.sinim != 0.0 {
+        rec.domdt -= rec.cosim / rec.sinim * shll;
+        rec.dnodt += shll / rec.sinim;
+    }
+
+    rec.dndt = 0.0;
+    let theta = (rec.gsto + tc * rptim) % TWOPI;
+    rec.em += rec.dedt * rec.t;
+    rec.inclm += rec.didt * rec.t;
+    rec.argpm += rec.domdt * rec.t;
+    rec.nodem += rec.dnodt * rec.t;
+    rec.mm += rec.dmdt * rec.t;
+
+    if rec.irez != 0 {
+        let aonv = (rec.nm / rec.xke).powf(x2o3);
+
+        if rec.irez == 2 {
+            let cosisq = rec.cosim * rec.cosim;
+            let emo = rec.em;
+            rec.em = rec.ecco;
+            let emsqo = rec.emsq;
+            rec.emsq = rec.eccsq;
+            let eoc = rec.em * rec.emsq;
+            let g201 = -0.306 - (rec.em - 0.64) * 0.440;
+
+            let g211;
+            let g310;
+            let g322;
+            let g410;
+            let g422;
+            let g520;
+            if rec.em <= 0.65 {
+                g211 = 3.616 - 13.2470 * rec.em + 16.2900 * rec.emsq;
+                g310 = -19.302 + 117.3900 * rec.em - 228.4190 * rec.emsq + 156.5910 * eoc;
+                g322 = -18.9068 + 109.7927 * rec.em - 214.6334 * rec.emsq + 146.5816 * eoc;
+                g410 = -41.122 + 242.6940 * rec.em - 471.0940 * rec.emsq + 313.9530 * eoc;
+                g422 = -146.407 + 841.8800 * rec.em - 1629.014 * rec.emsq + 1083.4350 * eoc;
+                g520 = -532.114 + 3017.977 * rec.em - 5740.032 * rec.emsq + 3708.2760 * eoc;
+            } else {
+                g211 = -72.099 + 331.819 * rec.em - 508.738 * rec.emsq + 266.724 * eoc;
+                g310 = -346.844 + 1582.851 * rec.em - 2415.925 * rec.emsq + 1246.113 * eoc;
+                g322 = -342.585 + 1554.908 * rec.em - 2366.899 * rec.emsq + 1215.972 * eoc;
+                g410 = -1052.797 + 4758.686 * rec.em - 7193.992 * rec.emsq + 3651.957 * eoc;
+                g422 = -3581.690 + 16178.110 * rec.em - 24462.770 * rec.emsq + 12422.520 * eoc;
+                if rec.em > 0.715 {
+                    g520 = -5149.66 + 29936.92 * rec.em - 54087.36 * rec.emsq + 31324.56 * eoc;
+                } else {
+                    g520 = 1464.74 - 4664.75 * rec.em + 3763.64 * rec.emsq;
+                }
+            }
+
+            let g533;
+            let g521;
+            let g532;
+            if rec.em < 0.7 {
+                g533 = -919.22770 + 4988.6100 * rec.em - 9064.7700 * rec.emsq + 5542.21 * eoc;
+                g521 = -822.71072 + 4568.6173 * rec.em - 8491.4146 * rec.emsq + 5337.524 * eoc;
+                g532 = -853.66600 + 4690.2500 * rec.em - 8624.7700 * rec.emsq + 5341.4 * eoc;
+            } else {
+                g533 = -37995.780 + 161616.52 * rec.em - 229838.20 * rec.emsq + 109377.94 * eoc;
+                g521 = -51752.104 + 218913.95 * rec.em - 309468.16 * rec.emsq + 146349.42 * eoc;
+                g532 = -40023.880 + 170470.89 * rec.em - 242699.48 * rec.emsq + 115605.82 * eoc;
+            }
+
+            let sini2 = rec.sinim * rec.sinim;
+            let f220 = 0.75 * (1.0 + 2.0 * rec.cosim + cosisq);
+            let f221 = 1.5 * sini2;
+            let f321 = 1.875 * rec.sinim * (1.0 - 2.0 * rec.cosim - 3.0 * cosisq);
+            let f322 = -1.875 * rec.sinim * (1.0 + 2.0 * rec.cosim - 3.0 * cosisq);
+            let f441 = 35.0 * sini2 * f220;
+            let f442 = 39.3750 * sini2 * sini2;
+            let f522 = 9.84375 * rec.sinim * (sini2 * (1.0 - 2.0 * rec.cosim - 5.0 * cosisq) +
+                0.33333333 * (-2.0 + 4.0 * rec.cosim + 6.0 * cosisq));
+            let f523 = rec.sinim * (4.92187512 * sini2 * (-2.0 - 4.0 * rec.cosim +
+                10.0 * cosisq) + 6.56250012 * (1.0 + 2.0 * rec.cosim - 3.0 * cosisq));
+            let f542 = 29.53125 * rec.sinim * (2.0 - 8.0 * rec.cosim + cosisq *
+                (-12.0 + 8.0 * rec.cosim + 10.0 * cosisq));
+            let f543 = 29.53125 * rec.sinim * (-2.0 - 8.0 * rec.cosim + cosisq *
+                (12.0 + 8.0 * rec.cosim - 10.0 * cosisq));
+            let xno2 = rec.nm * rec.nm;
+            let ainv2 = aonv * aonv;
+            let mut temp1 = 3.0 * xno2 * ainv2;
+            let mut temp = temp1 * root22;
+            rec.d2201 = temp * f220 * g201;
+            rec.d2211 = temp * f221 * g211;
+            temp1 *= aonv;
+            temp = temp1 * root32;
+            rec.d3210 = temp * f321 * g310;
+            rec.d3222 = temp * f322 * g322;
+            temp1 *= aonv;
+            temp = 2.0 * temp1 * root44;
+            rec.d4410 = temp * f441 * g410;
+            rec.d4422 = temp * f442 * g422;
+            temp1 *= aonv;
+            temp = temp1 * root52;
+            rec.d5220 = temp * f522 * g520;
+            rec.d5232 = temp * f523 * g532;
+            temp = 2.0 * temp1 * root54;
+            rec.d5421 = temp * f542 * g521;
+            rec.d5433 = temp * f543 * g533;
+            rec.xlamo = (rec.mo + rec.nodeo + rec.nodeo - theta - theta) % TWOPI;
+            rec.xfact = rec.mdot + rec.dmdt + 2.0 * (rec.nodedot + rec.dnodt - rptim) - rec.no_unkozai;
+            rec.em = emo;
+            rec.emsq = emsqo;
+        }
+
+        if rec.irez == 1 {
+            let g200 = 1.0 + rec.emsq * (-2.5 + 0.8125 * rec.emsq);
+            let g310 = 1.0 + 2.0 * rec.emsq;
+            let g300 = 1.0 + rec.emsq * (-6.0 + 6.60937 * rec.emsq);
+            let f220 = 0.75 * (1.0 + rec.cosim) * (1.0 + rec.cosim);
+            let f311 = 0.9375 * rec.sinim * rec.sinim * (1.0 + 3.0 * rec.cosim) - 0.75 * (1.0 + rec.cosim);
+            let mut f330 = 1.0 + rec.cosim;
+            f330 = 1.875 * f330 * f330 * f330;
+            rec.del1 = 3.0 * rec.nm * rec.nm * aonv * aonv;
+            rec.del2 = 2.0 * rec.del1 * f220 * g200 * q22;
+            rec.del3 = 3.0 * rec.del1 * f330 * g300 * q33 * aonv;
+            rec.del1 = rec.del1 * f311 * g310 * q31 * aonv;
+            rec.xlamo = (rec.mo + rec.nodeo + rec.argpo - theta) % TWOPI;
+            rec.xfact = rec.mdot + xpidot - rptim + rec.dmdt + rec.domdt + rec.dnodt - rec.no_unkozai;
+        }
+
+        rec.xli = rec.xlamo;
+        rec.xni = rec.no_unkozai;
+        rec.atime = 0.0;
+        rec.nm = rec.no_unkozai + rec.dndt;
+    }
+}
+
+/// Deep space secular effects, including the numerical integration of the
+/// resonance terms.
+fn dspace(tc: f64, rec: &mut ElsetRec) {
+    let fasx2 = 0.13130908;
+    let fasx4 = 2.8843198;
+    let fasx6 = 0.37448087;
+    let g22 = 5.7686396;
+    let g32 = 0.95240898;
+    let g44 = 1.8014998;
+    let g52 = 1.0508330;
+    let g54 = 4.4108898;
+    let rptim = 4.37526908801129966e-3;
+    let stepp = 720.0;
+    let stepn = -720.0;
+    let step2 = 259200.0;
+
+    rec.dndt = 0.0;
+    let theta = (rec.gsto + tc * rptim) % TWOPI;
+    rec.em += rec.dedt * rec.t;
+    rec.inclm += rec.didt * rec.t;
+    rec.argpm += rec.domdt * rec.t;
+    rec.nodem += rec.dnodt * rec.t;
+    rec.mm += rec.dmdt * rec.t;
+
+    if rec.irez != 0 {
+        if rec.atime == 0.0 || rec.t * rec.atime <= 0.0 || rec.t.abs() < rec.atime.abs() {
+            rec.atime = 0.0;
+            rec.xni = rec.no_unkozai;
+            rec.xli = rec.xlamo;
+        }
+
+        let delt = if rec.t > 0.0 { stepp } else { stepn };
+
+        let mut xndt;
+        let mut xnddt;
+        let mut xldot;
+        let ft;
+        loop {
+            if rec.irez != 2 {
+                xndt = rec.del1 * (rec.xli - fasx2).sin() + rec.del2 * (2.0 * (rec.xli - fasx4)).sin() +
+                    rec.del3 * (3.0 * (rec.xli - fasx6)).sin();
+                xldot = rec.xni + rec.xfact;
+                xnddt = rec.del1 * (rec.xli - fasx2).cos() +
+                    2.0 * rec.del2 * (2.0 * (rec.xli - fasx4)).cos() +
+                    3.0 * rec.del3 * (3.0 * (rec.xli - fasx6)).cos();
+                xnddt *= xldot;
+            } else {
+                let xomi = rec.argpo + rec.argpdot * rec.atime;
+                let x2omi = xomi + xomi;
+                let x2li = rec.xli + rec.xli;
+                xndt = rec.d2201 * (x2omi + rec.xli - g22).sin() + rec.d2211 * (rec.xli - g22).sin() +
+                    rec.d3210 * (xomi + rec.xli - g32).sin() + rec.d3222 * (-xomi + rec.xli - g32).sin() +
+                    rec.d4410 * (x2omi + x2li - g44).sin() + rec.d4422 * (x2li - g44).sin() +
+                    rec.d5220 * (xomi + rec.xli - g52).sin() + rec.d5232 * (-xomi + rec.xli - g52).sin() +
+                    rec.d5421 * (xomi + x2li - g54).sin() + rec.d5433 * (-xomi + x2li - g54).sin();
+                xldot = rec.xni + rec.xfact;
+                xnddt = rec.d2201 * (x2omi + rec.xli - g22).cos() + rec.d2211 * (rec.xli - g22).cos() +
+                    rec.d3210 * (xomi + rec.xli - g32).cos() + rec.d3222 * (-xomi + rec.xli - g32).cos() +
+                    rec.d5220 * (xomi + rec.xli - g52).cos() + rec.d5232 * (-xomi + rec.xli - g52).cos() +
+                    2.0 * (rec.d4410 * (x2omi + x2li - g44).cos() +
+                        rec.d4422 * (x2li - g44).cos() + rec.d5421 * (xomi + x2li - g54).cos() +
+                        rec.d5433 * (-xomi + x2li - g54).cos());
+                xnddt *= xldot;
+            }
+
+            if (rec.t - rec.atime).abs() < stepp {
+                ft = rec.t - rec.atime;
+                break;
+            }
+
+            rec.xli = rec.xli + xldot * delt + xndt * step2;
+            rec.xni = rec.xni + xndt * delt + xnddt * step2;
+            rec.atime += delt;
+        }
+
+        rec.nm = rec.xni + xndt * ft + xnddt * ft * ft * 0.5;
+        let xl = rec.xli + xldot * ft + xndt * ft * ft * 0.5;
+        if rec.irez != 1 {
+            rec.mm = xl - 2.0 * rec.nodem + 2.0 * theta;
+            rec.dndt = rec.nm - rec.no_unkozai;
+        } else {
+            rec.mm = xl - rec.nodem - rec.argpm + theta;
+            rec.dndt = rec.nm - rec.no_unkozai;
+        }
+        rec.nm = rec.no_unkozai + rec.dndt;
+    }
+}
+
+/// Initialize the sgp4 propagator, un-kozai the mean motion and compute the
+/// sidereal time at epoch.
+fn initl(epoch: f64, rec: &mut ElsetRec) {
+    let x2o3 = 2.0 / 3.0;
+
+    rec.eccsq = rec.ecco * rec.ecco;
+    rec.omeosq = 1.0 - rec.eccsq;
+    rec.rteosq = rec.omeosq.sqrt();
+    rec.cosio = rec.inclo.cos();
+    rec.cosio2 = rec.cosio * rec.cosio;
+
+    let ak = (rec.xke / rec.no_kozai).powf(x2o3);
+    let d1 = 0.75 * rec.j2 * (3.0 * rec.cosio2 - 1.0) / (rec.rteosq * rec.omeosq);
+    let mut del = d1 / (ak * ak);
+    let adel = ak * (1.0 - del * del - del *
+        (1.0 / 3.0 + 134.0 * del * del / 81.0));
+    del = d1 / (adel * adel);
+    rec.no_unkozai = rec.no_kozai / (1.0 + del);
+
+    rec.ao = (rec.xke / rec.no_unkozai).powf(x2o3);
+    rec.sinio = rec.inclo.sin();
+    let po = rec.ao * rec.omeosq;
+    rec.con42 = 1.0 - 5.0 * rec.cosio2;
+    rec.con41 = -rec.con42 - rec.cosio2 - rec.cosio2;
+    rec.ainv = 1.0 / rec.ao;
+    rec.posq = po * po;
+    rec.rp = rec.ao * (1.0 - rec.ecco);
+    rec.method = b'n';
+
+    rec.gsto = gstime(epoch + 2433281.5);
+}
+
+/// Initialize the variables for SGP4.
+///
+/// The elements, the epoch and the gravitational constants set must already be
+/// set in the record. Return false if the elements are invalid, the error code
+/// is then available in `satrec.error`.
+pub fn sgp4init(opsmode: u8, satrec: &mut ElsetRec) -> bool {
+    let epoch = (satrec.jdsatepoch + satrec.jdsatepochF) - 2433281.5;
+    let temp4 = 1.5e-12;
+
+    /* All the intermediate values are reset, only the elements are kept */
+    *satrec = ElsetRec {
+        whichconst: satrec.whichconst,
+        jdsatepoch: satrec.jdsatepoch,
+        jdsatepochF: satrec.jdsatepochF,
+        bstar: satrec.bstar,
+        inclo: satrec.inclo,
+        nodeo: satrec.nodeo,
+        ecco: satrec.ecco,
+        argpo: satrec.argpo,
+        mo: satrec.mo,
+        no_kozai: satrec.no_kozai,
+        ..Default::default()
+    };
+
+    getgravconst(satrec.whichconst, satrec);
+
+    satrec.error = 0;
+    satrec.operationmode = opsmode;
+    satrec.method = b'n';
+
+    let ss = 78.0 / satrec.radiusearthkm + 1.0;
+    let qzms2ttemp = (120.0 - 78.0) / satrec.radiusearthkm;
+    let qzms2t = qzms2ttemp * qzms2ttemp * qzms2ttemp * qzms2ttemp;
+    let x2o3 = 2.0 / 3.0;
+
+    satrec.init = b'y';
+    satrec.t = 0.0;
+
+    initl(epoch, satrec);
+
+    satrec.a = (satrec.no_unkozai * satrec.tumin).powf(-2.0 / 3.0);
+    satrec.alta = satrec.a * (1.0 + satrec.ecco) - 1.0;
+    satrec.altp = satrec.a * (1.0 - satrec.ecco) - 1.0;
+    satrec.error = 0;
+
+    if satrec.omeosq >= 0.0 || satrec.no_unkozai >= 0.0 {
+        satrec.isimp = 0;
+        if satrec.rp < (220.0 / satrec.radiusearthkm + 1.0) {
+            satrec.isimp = 1;
+        }
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (satrec.rp - 1.0) * satrec.radiusearthkm;
+
+        if perige < 156.0 {
+            sfour = perige - 78.0;
+            if perige < 98.0 {
+                sfour = 20.0;
+            }
+            let qzms24temp = (120.0 - sfour) / satrec.radiusearthkm;
+            qzms24 = qzms24temp * qzms24temp * qzms24temp * qzms24temp;
+            sfour = sfour / satrec.radiusearthkm + 1.0;
+        }
+        let pinvsq = 1.0 / satrec.posq;
+
+        let tsi = 1.0 / (satrec.ao - sfour);
+        satrec.eta = satrec.ao * satrec.ecco * tsi;
+        let etasq = satrec.eta * satrec.eta;
+        let eeta = satrec.ecco * satrec.eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powf(4.0);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1 * satrec.no_unkozai * (satrec.ao * (1.0 + 1.5 * etasq + eeta *
+            (4.0 + etasq)) + 0.375 * satrec.j2 * tsi / psisq * satrec.con41 *
+            (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        satrec.cc1 = satrec.bstar * cc2;
+        let mut cc3 = 0.0;
+        if satrec.ecco > 1.0e-4 {
+            cc3 = -2.0 * coef * tsi * satrec.j3oj2 * satrec.no_unkozai * satrec.sinio / satrec.ecco;
+        }
+        satrec.x1mth2 = 1.0 - satrec.cosio2;
+        satrec.cc4 = 2.0 * satrec.no_unkozai * coef1 * satrec.ao * satrec.omeosq *
+            (satrec.eta * (2.0 + 0.5 * etasq) + satrec.ecco *
+                (0.5 + 2.0 * etasq) - satrec.j2 * tsi / (satrec.ao * psisq) *
+                (-3.0 * satrec.con41 * (1.0 - 2.0 * eeta + etasq *
+                    (1.5 - 0.5 * eeta)) + 0.75 * satrec.x1mth2 *
+                    (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * satrec.argpo).cos()));
+        satrec.cc5 = 2.0 * coef1 * satrec.ao * satrec.omeosq * (1.0 + 2.75 *
+            (etasq + eeta) + eeta * etasq);
+        let cosio4 = satrec.cosio2 * satrec.cosio2;
+        let temp1 = 1.5 * satrec.j2 * pinvsq * satrec.no_unkozai;
+        let temp2 = 0.5 * temp1 * satrec.j2 * pinvsq;
+        let temp3 = -0.46875 * satrec.j4 * pinvsq * pinvsq * satrec.no_unkozai;
+        satrec.mdot = satrec.no_unkozai + 0.5 * temp1 * satrec.rteosq * satrec.con41 + 0.0625 *
+            temp2 * satrec.rteosq * (13.0 - 78.0 * satrec.cosio2 + 137.0 * cosio4);
+        satrec.argpdot = -0.5 * temp1 * satrec.con42 + 0.0625 * temp2 *
+            (7.0 - 114.0 * satrec.cosio2 + 395.0 * cosio4) +
+            temp3 * (3.0 - 36.0 * satrec.cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * satrec.cosio;
+        satrec.nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * satrec.cosio2) +
+            2.0 * temp3 * (3.0 - 7.0 * satrec.cosio2)) * satrec.cosio;
+        let xpidot = satrec.argpdot + satrec.nodedot;
+        satrec.omgcof = satrec.bstar * cc3 * satrec.argpo.cos();
+        satrec.xmcof = 0.0;
+        if satrec.ecco > 1.0e-4 {
+            satrec.xmcof = -x2o3 * coef * satrec.bstar / eeta;
+        }
+        satrec.nodecf = 3.5 * satrec.omeosq * xhdot1 * satrec.cc1;
+        satrec.t2cof = 1.5 * satrec.cc1;
+        if (satrec.cosio + 1.0).abs() > 1.5e-12 {
+            satrec.xlcof = -0.25 * satrec.j3oj2 * satrec.sinio * (3.0 + 5.0 * satrec.cosio) / (1.0 + satrec.cosio);
+        } else {
+            satrec.xlcof = -0.25 * satrec.j3oj2 * satrec.sinio * (3.0 + 5.0 * satrec.cosio) / temp4;
+        }
+        satrec.aycof = -0.5 * satrec.j3oj2 * satrec.sinio;
+        let delmotemp = 1.0 + satrec.eta * satrec.mo.cos();
+        satrec.delmo = delmotemp * delmotemp * delmotemp;
+        satrec.sinmao = satrec.mo.sin();
+        satrec.x7thm1 = 7.0 * satrec.cosio2 - 1.0;
+
+        /* Deep space initialization */
+        if (2.0 * PI / satrec.no_unkozai) >= 225.0 {
+            satrec.method = b'd';
+            satrec.isimp = 1;
+            let tc = 0.0;
+            satrec.inclm = satrec.inclo;
+
+            dscom(epoch, satrec.ecco, satrec.argpo, tc, satrec.inclo, satrec.nodeo, satrec.no_unkozai, satrec);
+
+            satrec.ep = satrec.ecco;
+            satrec.inclp = satrec.inclo;
+            satrec.nodep = satrec.nodeo;
+            satrec.argpp = satrec.argpo;
+            satrec.mp = satrec.mo;
+
+            let init = satrec.init;
+            let opsmode = satrec.operationmode;
+            dpper(satrec, init, opsmode);
+
+            satrec.ecco = satrec.ep;
+            satrec.inclo = satrec.inclp;
+            satrec.nodeo = satrec.nodep;
+            satrec.argpo = satrec.argpp;
+            satrec.mo = satrec.mp;
+
+            satrec.argpm = 0.0;
+            satrec.nodem = 0.0;
+            satrec.mm = 0.0;
+
+            dsinit(tc, xpidot, satrec);
+        }
+
+        /* Set variables if not deep space */
+        if satrec.isimp != 1 {
+            let cc1sq = satrec.cc1 * satrec.cc1;
+            satrec.d2 = 4.0 * satrec.ao * tsi * cc1sq;
+            let temp = satrec.d2 * tsi * satrec.cc1 / 3.0;
+            satrec.d3 = (17.0 * satrec.ao + sfour) * temp;
+            satrec.d4 = 0.5 * temp * satrec.ao * tsi * (221.0 * satrec.ao + 31.0 * sfour) * satrec.cc1;
+            satrec.t3cof = satrec.d2 + 2.0 * cc1sq;
+            satrec.t4cof = 0.25 * (3.0 * satrec.d3 + satrec.cc1 *
+                (12.0 * satrec.d2 + 10.0 * cc1sq));
+            satrec.t5cof = 0.2 * (3.0 * satrec.d4 +
+                12.0 * satrec.cc1 * satrec.d3 +
+                6.0 * satrec.d2 * satrec.d2 +
+                15.0 * cc1sq * (2.0 * satrec.d2 + cc1sq));
+        }
+    }
+
+    /* Propagate to the epoch to initialize the remaining variables */
+    let mut r = [0.0; 3];
+    let mut v = [0.0; 3];
+    sgp4(satrec, 0.0, &mut r, &mut v);
+
+    satrec.init = b'n';
+
+    true
+}
+
+/// Propagate the satellite `tsince` minutes after the epoch.
+///
+/// The position (km) and velocity (km/s) are written in `r` and `v`, in the TEME
+/// frame. Return false on error, the error code is then available in
+/// `satrec.error`.
+pub fn sgp4(satrec: &mut ElsetRec, tsince: f64, r: &mut [f64; 3], v: &mut [f64; 3]) -> bool {
+    let temp4 = 1.5e-12;
+    let x2o3 = 2.0 / 3.0;
+    let vkmpersec = satrec.radiusearthkm * satrec.xke / 60.0;
+
+    /* Update for secular gravity and atmospheric drag */
+    satrec.t = tsince;
+    satrec.error = 0;
+
+    let xmdf = satrec.mo + satrec.mdot * satrec.t;
+    let argpdf = satrec.argpo + satrec.argpdot * satrec.t;
+    let nodedf = satrec.nodeo + satrec.nodedot * satrec.t;
+    satrec.argpm = argpdf;
+    satrec.mm = xmdf;
+    let t2 = satrec.t * satrec.t;
+    satrec.nodem = nodedf + satrec.nodecf * t2;
+    let mut tempa = 1.0 - satrec.cc1 * satrec.t;
+    let mut tempe = satrec.bstar * satrec.cc4 * satrec.t;
+    let mut templ = satrec.t2cof * t2;
+
+    if satrec.isimp != 1 {
+        let delomg = satrec.omgcof * satrec.t;
+        let delmtemp = 1.0 + satrec.eta * xmdf.cos();
+        let delm = satrec.xmcof *
+            (delmtemp * delmtemp * delmtemp -
+                satrec.delmo);
+        let temp = delomg + delm;
+        satrec.mm = xmdf + temp;
+        satrec.argpm = argpdf - temp;
+        let t3 = t2 * satrec.t;
+        let t4 = t3 * satrec.t;
+        tempa = tempa - satrec.d2 * t2 - satrec.d3 * t3 -
+            satrec.d4 * t4;
+        tempe += satrec.bstar * satrec.cc5 * (satrec.mm.sin() - satrec.sinmao);
+        templ = templ + satrec.t3cof * t3 + t4 * (satrec.t4cof + satrec.t * satrec.t5cof);
+    }
+
+    satrec.nm = satrec.no_unkozai;
+    satrec.em = satrec.ecco;
+    satrec.inclm = satrec.inclo;
+    if satrec.method == b'd' {
+        let tc = satrec.t;
+        dspace(tc, satrec);
+    }
+
+    if satrec.nm <= 0.0 {
+        satrec.error = 2;
+        return false;
+    }
+
+    satrec.am = (satrec.xke / satrec.nm).powf(x2o3) * tempa * tempa;
+    satrec.nm = satrec.xke / satrec.am.powf(1.5);
+    satrec.em -= tempe;
+
+    if satrec.em >= 1.0 || satrec.em < -0.001 {
+        satrec.error = 1;
+        return false;
+    }
+
+    if satrec.em < 1.0e-6 {
+        satrec.em = 1.0e-6;
+    }
+    satrec.mm += satrec.no_unkozai * templ;
+    let mut xlm = satrec.mm + satrec.argpm + satrec.nodem;
+    satrec.emsq = satrec.em * satrec.em;
+
+    satrec.nodem %= TWOPI;
+    satrec.argpm %= TWOPI;
+    xlm %= TWOPI;
+    satrec.mm = (xlm - satrec.argpm - satrec.nodem) % TWOPI;
+
+    /* Record the current mean elements */
+    satrec.im = satrec.inclm;
+    satrec.Om = satrec.nodem;
+    satrec.om = satrec.argpm;
+
+    /* Compute extra mean quantities */
+    satrec.sinim = satrec.inclm.sin();
+    satrec.cosim = satrec.inclm.cos();
+
+    /* Add lunar-solar periodics */
+    satrec.ep = satrec.em;
+    let mut xincp = satrec.inclm;
+    satrec.inclp = satrec.inclm;
+    satrec.argpp = satrec.argpm;
+    satrec.nodep = satrec.nodem;
+    satrec.mp = satrec.mm;
+    let mut sinip = satrec.sinim;
+    let mut cosip = satrec.cosim;
+
+    if satrec.method == b'd' {
+        let opsmode = satrec.operationmode;
+        dpper(satrec, b'n', opsmode);
+
+        xincp = satrec.inclp;
+        if xincp < 0.0 {
+            xincp = -xincp;
+            satrec.nodep += PI;
+            satrec.argpp -= PI;
+        }
+        if satrec.ep < 0.0 || satrec.ep > 1.0 {
+            satrec.error = 3;
+            return false;
+        }
+
+        /* Long period periodics */
+        sinip = xincp.sin();
+        cosip = xincp.cos();
+        satrec.aycof = -0.5 * satrec.j3oj2 * sinip;
+        if (cosip + 1.0).abs() > 1.5e-12 {
+            satrec.xlcof = -0.25 * satrec.j3oj2 * sinip * (3.0 + 5.0 * cosip) / (1.0 + cosip);
+        } else {
+            satrec.xlcof = -0.25 * satrec.j3oj2 * sinip * (3.0 + 5.0 * cosip) / temp4;
+        }
+    }
+
+    let axnl = satrec.ep * satrec.argpp.cos();
+    let mut temp = 1.0 / (satrec.am * (1.0 - satrec.ep * satrec.ep));
+    let aynl = satrec.ep * satrec.argpp.sin() + temp * satrec.aycof;
+    let xl = satrec.mp + satrec.argpp + satrec.nodep + temp * satrec.xlcof * axnl;
+
+    /* Solve Kepler's equation */
+    let u = (xl - satrec.nodep) % TWOPI;
+    let mut eo1 = u;
+    let mut tem5: f64 = 9999.9;
+    let mut ktr = 1;
+    let mut sineo1 = 0.0;
+    let mut coseo1 = 0.0;
+
+    while tem5.abs() >= 1.0e-12 && ktr <= 10 {
+        sineo1 = eo1.sin();
+        coseo1 = eo1.cos();
+        tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+        tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+        if tem5.abs() >= 0.95 {
+            tem5 = if tem5 > 0.0 { 0.95 } else { -0.95 };
+        }
+        eo1 += tem5;
+        ktr += 1;
+    }
+
+    /* Short period preliminary quantities */
+    let ecose = axnl * coseo1 + aynl * sineo1;
+    let esine = axnl * sineo1 - aynl * coseo1;
+    let el2 = axnl * axnl + aynl * aynl;
+    let pl = satrec.am * (1.0 - el2);
+    if pl < 0.0 {
+        satrec.error = 4;
+        return false;
+    }
+
+    let rl = satrec.am * (1.0 - ecose);
+    let rdotl = satrec.am.sqrt() * esine / rl;
+    let rvdotl = pl.sqrt() / rl;
+    let betal = (1.0 - el2).sqrt();
+    temp = esine / (1.0 + betal);
+    let sinu = satrec.am / rl * (sineo1 - aynl - axnl * temp);
+    let cosu = satrec.am / rl * (coseo1 - axnl + aynl * temp);
+    let mut su = sinu.atan2(cosu);
+    let sin2u = (cosu + cosu) * sinu;
+    let cos2u = 1.0 - 2.0 * sinu * sinu;
+    temp = 1.0 / pl;
+    let temp1 = 0.5 * satrec.j2 * temp;
+    let temp2 = temp1 * temp;
+
+    /* Update for short period periodics */
+    if satrec.method == b'd' {
+        let cosisq = cosip * cosip;
+        satrec.con41 = 3.0 * cosisq - 1.0;
+        satrec.x1mth2 = 1.0 - cosisq;
+        satrec.x7thm1 = 7.0 * cosisq - 1.0;
+    }
+    let mrt = rl * (1.0 - 1.5 * temp2 * betal * satrec.con41) +
+        0.5 * temp1 * satrec.x1mth2 * cos2u;
+    su -= 0.25 * temp2 * satrec.x7thm1 * sin2u;
+    let xnode = satrec.nodep + 1.5 * temp2 * cosip * sin2u;
+    let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+    let mvt = rdotl - satrec.nm * temp1 * satrec.x1mth2 * sin2u / satrec.xke;
+    let rvdot = rvdotl + satrec.nm * temp1 * (satrec.x1mth2 * cos2u +
+        1.5 * satrec.con41) / satrec.xke;
+
+    /* Orientation vectors */
+    let sinsu = su.sin();
+    let cossu = su.cos();
+    let snod = xnode.sin();
+    let cnod = xnode.cos();
+    let sini = xinc.sin();
+    let cosi = xinc.cos();
+    let xmx = -snod * cosi;
+    let xmy = cnod * cosi;
+    let ux = xmx * sinsu + cnod * cossu;
+    let uy = xmy * sinsu + snod * cossu;
+    let uz = sini * sinsu;
+    let vx = xmx * cossu - cnod * sinsu;
+    let vy = xmy * cossu - snod * sinsu;
+    let vz = sini * cossu;
+
+    /* Position and velocity (in km and km/sec) */
+    r[0] = (mrt * ux) * satrec.radiusearthkm;
+    r[1] = (mrt * uy) * satrec.radiusearthkm;
+    r[2] = (mrt * uz) * satrec.radiusearthkm;
+    v[0] = (mvt * ux + rvdot * vx) * vkmpersec;
+    v[1] = (mvt * uy + rvdot * vy) * vkmpersec;
+    v[2] = (mvt * uz + rvdot * vz) * vkmpersec;
+
+    /* The satellite has decayed */
+    if mrt < 1.0 {
+        satrec.error = 6;
+        return false;
+    }
+
+    true
+}
+
+/// Set the gravitational constants of the given set in the record.
+fn getgravconst(whichconst: i32, rec: &mut ElsetRec) {
+    rec.whichconst = whichconst;
+    match whichconst {
+        WGS72OLD => {
+            rec.mu = 398600.79964;
+            rec.radiusearthkm = 6378.135;
+            rec.xke = 0.0743669161;
+            rec.tumin = 1.0 / rec.xke;
+            rec.j2 = 0.001082616;
+            rec.j3 = -0.00000253881;
+            rec.j4 = -0.00000165597;
+            rec.j3oj2 = rec.j3 / rec.j2;
+        }
+        WGS72 => {
+            rec.mu = 398600.8;
+            rec.radiusearthkm = 6378.135;
+            rec.xke = 60.0 / (rec.radiusearthkm * rec.radiusearthkm * rec.radiusearthkm / rec.mu).sqrt();
+            rec.tumin = 1.0 / rec.xke;
+            rec.j2 = 0.001082616;
+            rec.j3 = -0.00000253881;
+            rec.j4 = -0.00000165597;
+            rec.j3oj2 = rec.j3 / rec.j2;
+        }
+        _ => {
+            rec.mu = 398600.5;
+            rec.radiusearthkm = 6378.137;
+            rec.xke = 60.0 / (rec.radiusearthkm * rec.radiusearthkm * rec.radiusearthkm / rec.mu).sqrt();
+            rec.tumin = 1.0 / rec.xke;
+            rec.j2 = 0.00108262998905;
+            rec.j3 = -0.00000253215306;
+            rec.j4 = -0.00000161098761;
+            rec.j3oj2 = rec.j3 / rec.j2;
+        }
+    }
+}
+
+/// Compute the Greenwich sidereal time (IAU-82) in radians for the given UT1
+/// Julian day.
+pub fn gstime(jdut1: f64) -> f64 {
+    let tut1 = (jdut1 - 2451545.0) / 36525.0;
+    let mut temp = -6.2e-6 * tut1 * tut1 * tut1 + 0.093104 * tut1 * tut1 +
+        (876600.0 * 3600.0 + 8640184.812866) * tut1 + 67310.54841;
+    temp = (temp * DEG2RAD / 240.0) % TWOPI;
+
+    if temp < 0.0 {
+        temp += TWOPI;
+    }
+
+    temp
+}
+
+/// Compute the Julian day of the given date, split in a day part (`jd`) and a
+/// fraction of day part (`jdfrac`).
+pub fn jday(year: i32, mon: i32, day: i32, hr: i32, minute: i32, sec: f64, jd: &mut f64, jdfrac: &mut f64) {
+    *jd = 367.0 * year as f64 -
+        ((7.0 * (year as f64 + ((mon + 9) as f64 / 12.0).floor())) * 0.25).floor() +
+        (275.0 * mon as f64 / 9.0).floor() +
+        day as f64 + 1721013.5;
+    *jdfrac = (sec + minute as f64 * 60.0 + hr as f64 * 3600.0) / 86400.0;
+
+    if jdfrac.abs() > 1.0 {
+        let dtt = jdfrac.floor();
+        *jd += dtt;
+        *jdfrac -= dtt;
+    }
+}
@@ -3,13 +3,13 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-/* This is a wrapper for the C SGP4 algoritm.
- * It provides a rust-like interface to use the sgp4 algorithm.
+/* This is a wrapper for the SGP4 algoritm.
+ * It provides a rust-like interface to use the sgp4 algorithm, whichever
+ * backend (C library or Rust port) was selected.
  */
 
-use std;
 use std::{
-    mem,
+    error,
     fmt,
     rc::Rc,
     cell::RefCell,
@@ -25,14 +25,16 @@ use chrono::{
 
 use crate::{
     ElsetRec,
-    sgp4,
-    sgp4init,
-    jday
+    backend,
 };
 
+mod partials;
+pub use partials::{PartialsError, PartialsSteps};
+
 ///
 /// # Represent an error of the SGP4 algorithm
 ///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SGP4Error {
     MeanElements, /* 1 - mean elements, ecc >= 1.0 or ecc < -0.001 or a < 0.95 er */
     MeanMotion,   /* 2 - mean motion less than 0.0 */
@@ -72,9 +74,12 @@ impl fmt::Display for SGP4Error {
     }
 }
 
+impl error::Error for SGP4Error {}
+
 /// # Represent an SGP4 operation mode
 /// Afscp if for the Air Force Space Command version of the algorithm,
 /// Improved is the same algorithm with performance improvements
+#[derive(Clone, Copy)]
 pub enum OpsMode {
     Afspc,
     Improved,
@@ -96,26 +101,88 @@ impl OpsMode {
 ///
 /// Note that the set of 1984 is more precise but it is recommended to use the one that was used
 /// to generate the TLE, which is usually 1972.
+#[derive(Clone, Copy)]
 pub enum ConstantsSet {
     Set72,
     Set84,
 }
 
 impl ConstantsSet {
+    /// Identifier of the set in the SGP4 library (`wgs72` or `wgs84`)
     pub fn to_int(&self) -> i32 {
         match self {
-            ConstantsSet::Set72 => 72,
-            ConstantsSet::Set84 => 84,
+            ConstantsSet::Set72 => 2,
+            ConstantsSet::Set84 => 3,
         }
     }
 }
 
+/// # Deep space resonance of an orbit
+/// Deep space orbits whose period is close to one day or half a day are subject to
+/// geopotential resonance, which SGP4 integrates numerically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resonance {
+    None,
+    /// Synchronous orbit (period of about 1 day, e.g. geostationary satellites)
+    Synchronous,
+    /// Half-day orbit with an eccentricity above 0.5 (e.g. Molniya orbits)
+    HalfDay,
+}
+
+impl Resonance {
+    fn from_irez(irez: i32) -> Resonance
+    {
+        match irez {
+            1 => Resonance::Synchronous,
+            2 => Resonance::HalfDay,
+            _ => Resonance::None,
+        }
+    }
+}
+
+/// # The SGP4 mean elements at the time of a computation
+/// These are the singly averaged elements, before the short period periodics are applied.
+#[derive(Debug, Clone, Copy)]
+pub struct MeanElements {
+    /// Semi-major axis in km
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    /// Inclination in radians
+    pub inclination: f64,
+    /// Right ascension of the ascending node in radians
+    pub ascending_node: f64,
+    /// Argument of perigee in radians
+    pub arg_perigee: f64,
+    /// Mean anomaly in radians
+    pub mean_anomaly: f64,
+    /// Mean motion in revolutions per day
+    pub mean_motion: f64,
+}
+
+impl MeanElements {
+    fn from_record(rec: &ElsetRec) -> MeanElements
+    {
+        MeanElements {
+            semi_major_axis: rec.am * rec.radiusearthkm,
+            eccentricity: rec.em,
+            inclination: rec.im,
+            ascending_node: rec.Om,
+            arg_perigee: rec.om,
+            mean_anomaly: rec.mm,
+            mean_motion: rec.nm * MINUTES_PER_DAY / (2.0 * PI),
+        }
+    }
+}
+
+const MINUTES_PER_DAY: f64 = 1440.0;
+
 /// # A SGP4 computation result
 /// It can be used to get a satellit speed, altitude, position vector and velocity vector
 pub struct SGP4Result {
     position: [f64; 3],
     velocity: [f64; 3],
     time: DateTime<Utc>,
+    mean_elements: MeanElements,
     satrec: Rc<RefCell<ElsetRec>>
 }
 
@@ -158,6 +225,12 @@ impl SGP4Result {
     {
         self.time
     }
+
+    /// Return the mean elements that were used to compute this result
+    pub fn mean_elements(&self) -> MeanElements
+    {
+        self.mean_elements
+    }
 }
 
 impl fmt::Display for SGP4Result {
@@ -169,13 +242,17 @@ impl fmt::Display for SGP4Result {
     }
 }
 
-/// SGP4 C library wrapper
+/// SGP4 library wrapper
 pub struct SGP4 {
-    /// The C structure that is used by the SGP4 library,
-    /// It is set in a RefCell because the FFI calls need to access it as mutable but the
+    /// The structure that is used by the SGP4 library,
+    /// It is set in a RefCell because the propagation needs to access it as mutable but the
     /// interface has no reason to force a mutable variable
     satrec: Rc<RefCell<ElsetRec>>,
     epoch: DateTime<Utc>,
+    mode: OpsMode,
+    const_set: ConstantsSet,
+    /// The elements used for the initialization, in the order of the partial derivatives
+    elements: [f64; 7],
 }
 
 impl SGP4 {
@@ -197,18 +274,18 @@ impl SGP4 {
                mean_motion: f64,
                ascending_node: f64) -> Result<SGP4, SGP4Error>
     {
-        let mut satrec: ElsetRec;
-        let ret: i32;
+        let mut satrec = backend::new_record();
 
         // This is used to convert the mean motion to an angular speed in rad / minute
         // from the argument that is in revolutions (2*PI)rad per day (1440 minutes))
         let xpdotp = (2.0 * PI) / 1440f64;
 
-        unsafe {
-            satrec = mem::zeroed();
-        }
+        // The deep space terms (sidereal time, positions of the Sun and the Moon) depend on the epoch
+        let (jd, jdfrac) = SGP4::julian_day_parts(epoch);
 
         satrec.whichconst = const_set.to_int();
+        satrec.jdsatepoch = jd;
+        satrec.jdsatepochF = jdfrac;
         satrec.bstar = bstar;
         satrec.ecco = eccentricity;
         satrec.argpo = arg_perigee;
@@ -217,37 +294,31 @@ impl SGP4 {
         satrec.no_kozai = mean_motion * xpdotp;
         satrec.nodeo = ascending_node;
 
-        unsafe {
-            ret = sgp4init(mode.to_char() as std::os::raw::c_char, &mut satrec as *mut ElsetRec);
-        }
-
-        if ret == 0 {
+        if !backend::init(mode.to_char(), &mut satrec) {
             return Err(SGP4Error::from_code(satrec.error));
         }
 
         Ok(SGP4 {
             satrec: Rc::new(RefCell::new(satrec)),
-            epoch
+            epoch,
+            mode,
+            const_set,
+            elements: [inclination, ascending_node, eccentricity, arg_perigee, mean_anomaly, mean_motion, bstar],
         })
     }
 
     /// Compute the velocity and position vectors at the given time.
     pub fn compute(&self, time: DateTime<Utc>) -> Result<SGP4Result, SGP4Error>
     {
-        let ret: i32;
         let minutes: f64 = time.signed_duration_since(self.epoch).num_milliseconds() as f64 / 60_000f64;
 
-        let mut r: [f64; 3] = [0f64, 0f64, 0f64]; //FIXME: is this allowed to be passed to a C function (use as_mut_ptr() ?) ?
+        let mut r: [f64; 3] = [0f64, 0f64, 0f64];
         let mut v: [f64; 3] = [0f64, 0f64, 0f64];
 
         let mut rec = (*self.satrec).borrow_mut();
 
-        unsafe {
-            ret = sgp4(&mut *rec as *mut ElsetRec, minutes, &mut r as *mut f64, &mut v as *mut f64);
-        }
-
-        // The sgp4 function returns a boolean value --> 0 is an error
-        if ret == 0 {
+        // The sgp4 function returns a boolean value --> false is an error
+        if !backend::propagate(&mut rec, minutes, &mut r, &mut v) {
             return Err(SGP4Error::from_code(rec.error));
         }
 
@@ -255,6 +326,7 @@ impl SGP4 {
             position: r,
             velocity: v,
             time,
+            mean_elements: MeanElements::from_record(&rec),
             satrec: Rc::clone(&self.satrec)
         })
     }
@@ -265,22 +337,85 @@ impl SGP4 {
         rec.radiusearthkm
     }
 
+    /// Return the un-Kozai'd mean motion at epoch, in revolutions per day
+    pub fn mean_motion(&self) -> f64
+    {
+        let rec = (*self.satrec).borrow();
+        rec.no_unkozai * MINUTES_PER_DAY / (2.0 * PI)
+    }
+
+    /// Return the mean semi-major axis at epoch, in km
+    pub fn semi_major_axis(&self) -> f64
+    {
+        let rec = (*self.satrec).borrow();
+        rec.a * rec.radiusearthkm
+    }
+
+    /// Return the altitude of the apogee at epoch, in km above the equatorial radius
+    pub fn apogee_altitude(&self) -> f64
+    {
+        let rec = (*self.satrec).borrow();
+        rec.alta * rec.radiusearthkm
+    }
+
+    /// Return the altitude of the perigee at epoch, in km above the equatorial radius
+    pub fn perigee_altitude(&self) -> f64
+    {
+        let rec = (*self.satrec).borrow();
+        rec.altp * rec.radiusearthkm
+    }
+
+    /// Return true if the deep space (SDP4) equations are used for this orbit,
+    /// which is the case for periods of 225 minutes or more
+    pub fn is_deep_space(&self) -> bool
+    {
+        let rec = (*self.satrec).borrow();
+        // The method is a c_char with the C backend
+        rec.method == b'd' as _
+    }
+
+    /// Return the geopotential resonance that is integrated for this orbit
+    pub fn resonance(&self) -> Resonance
+    {
+        let rec = (*self.satrec).borrow();
+        Resonance::from_irez(rec.irez)
+    }
+
+    /// Return the mean elements of the last computation (or at epoch if nothing was
+    /// computed yet).
+    ///
+    /// This is useful to understand why a propagation failed, e.g. an eccentricity
+    /// that went above 1, but not every element is updated when it fails:
+    ///  - after `PertElements`, `SemiLatus` or `Decayed`, all the elements are those of
+    ///    the failed computation
+    ///  - after `MeanElements`, only the semi-major axis, the eccentricity and the mean
+    ///    motion are; the inclination, the ascending node and the argument of perigee are
+    ///    still those of the previous computation, and the mean anomaly is only partially
+    ///    updated
+    ///  - after `MeanMotion`, only the mean motion is
+    pub fn mean_elements(&self) -> MeanElements
+    {
+        let rec = (*self.satrec).borrow();
+        MeanElements::from_record(&rec)
+    }
+
     //Compute the Julian Day corresponding to the given date/time
     pub fn julian_day(time: DateTime<Utc>) -> f64
     {
-        let mut jd: f64 = 0.0;
-        let mut jdfrac: f64 = 0.0;
-
-        unsafe {
-            jday(time.date().year(),
-                       time.date().month() as i32,
-                       time.date().day() as i32,
-                       time.time().hour() as i32,
-                       time.time().minute() as i32,
-                       time.time().second() as f64 + (time.time().nanosecond() as f64) / 10e9,
-                       &mut jd as *mut f64, &mut jdfrac as *mut f64);
-        }
+        let (jd, jdfrac) = SGP4::julian_day_parts(time);
 
         jd + jdfrac
     }
+
+    /// Julian day of the given date/time as (day at midnight, fraction of day), without the
+    /// rounding of a single f64
+    fn julian_day_parts(time: DateTime<Utc>) -> (f64, f64)
+    {
+        backend::jday(time.date_naive().year(),
+                      time.date_naive().month() as i32,
+                      time.date_naive().day() as i32,
+                      time.time().hour() as i32,
+                      time.time().minute() as i32,
+                      time.time().second() as f64 + (time.time().nanosecond() as f64) / 1e9)
+    }
 }
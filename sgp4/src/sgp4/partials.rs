@@ -0,0 +1,267 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Sensitivity of the SGP4 state to the elements.
+ *
+ * SGP4 is an analytical theory with many branches (deep space, resonances,
+ * low perigee,...), so the derivatives are computed by central finite
+ * differences: the propagator is initialized again with each element shifted
+ * by +/- its step.
+ */
+
+use std::{
+    error,
+    fmt,
+};
+
+use chrono::{DateTime, Utc};
+
+use super::{SGP4, SGP4Error};
+
+const INCLINATION: usize = 0;
+const ASCENDING_NODE: usize = 1;
+const ECCENTRICITY: usize = 2;
+const ARG_PERIGEE: usize = 3;
+const MEAN_ANOMALY: usize = 4;
+const MEAN_MOTION: usize = 5;
+const BSTAR: usize = 6;
+
+/// # Represent an error of the state transition matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartialsError {
+    /// A propagation of the shifted elements failed
+    Propagation(SGP4Error),
+    /// The partial derivatives cannot be inverted (degenerate elements)
+    Singular,
+}
+
+impl From<SGP4Error> for PartialsError {
+    fn from(e: SGP4Error) -> Self {
+        PartialsError::Propagation(e)
+    }
+}
+
+impl fmt::Display for PartialsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartialsError::Propagation(e) => write!(f, "{}", e),
+            PartialsError::Singular => write!(f, "Singular partial derivatives"),
+        }
+    }
+}
+
+impl error::Error for PartialsError {}
+
+/// # Steps used to compute the partial derivatives by finite differences
+/// Each element is shifted by + and - its step. The steps are given in the units of the
+/// elements passed to `SGP4::new` (radians, revolutions per day, earth radii^-1 for B*).
+#[derive(Debug, Clone, Copy)]
+pub struct PartialsSteps {
+    pub inclination: f64,
+    pub ascending_node: f64,
+    pub eccentricity: f64,
+    pub arg_perigee: f64,
+    pub mean_anomaly: f64,
+    pub mean_motion: f64,
+    pub bstar: f64,
+}
+
+impl PartialsSteps {
+    fn to_array(self) -> [f64; 7]
+    {
+        let mut steps = [0.0; 7];
+
+        steps[INCLINATION] = self.inclination;
+        steps[ASCENDING_NODE] = self.ascending_node;
+        steps[ECCENTRICITY] = self.eccentricity;
+        steps[ARG_PERIGEE] = self.arg_perigee;
+        steps[MEAN_ANOMALY] = self.mean_anomaly;
+        steps[MEAN_MOTION] = self.mean_motion;
+        steps[BSTAR] = self.bstar;
+
+        steps
+    }
+}
+
+impl Default for PartialsSteps {
+    /// Steps suited to the usual TLE precision
+    fn default() -> Self {
+        PartialsSteps {
+            inclination: 1e-5,
+            ascending_node: 1e-5,
+            eccentricity: 1e-6,
+            arg_perigee: 1e-5,
+            mean_anomaly: 1e-5,
+            mean_motion: 1e-6,
+            bstar: 1e-6,
+        }
+    }
+}
+
+impl SGP4 {
+    /// Compute the partial derivatives of the state at the given time with respect to the
+    /// elements.
+    ///
+    /// The rows are the state components: position [km] then velocity [km/s].
+    /// The columns are the elements: inclination, right ascension of the ascending node,
+    /// eccentricity, argument of perigee, mean anomaly, mean motion and B*.
+    pub fn partials(&self, time: DateTime<Utc>, steps: &PartialsSteps) -> Result<[[f64; 7]; 6], SGP4Error>
+    {
+        let mut partials = [[0.0; 7]; 6];
+
+        for (element, step) in steps.to_array().iter().enumerate() {
+            let derivative = self.element_derivative(time, element, *step)?;
+
+            for (row, value) in derivative.iter().enumerate() {
+                partials[row][element] = *value;
+            }
+        }
+
+        Ok(partials)
+    }
+
+    /// Compute the state transition matrix from the state at `from` to the state at `to`.
+    ///
+    /// The matrix maps a small deviation of the state (position [km], velocity [km/s]) at
+    /// `from` to the resulting deviation at `to`. It is obtained by composing the partial
+    /// derivatives of both states with respect to the 6 orbital elements (B* is kept
+    /// constant). For nearly circular or equatorial orbits, these elements are nearly
+    /// degenerate and `PartialsError::Singular` can be returned.
+    pub fn state_transition_matrix(&self,
+                                   from: DateTime<Utc>,
+                                   to: DateTime<Utc>,
+                                   steps: &PartialsSteps) -> Result<[[f64; 6]; 6], PartialsError>
+    {
+        let partials_from = self.partials(from, steps)?;
+        let partials_to = self.partials(to, steps)?;
+
+        let inverse = match invert(orbital_columns(&partials_from)) {
+            Some(m) => m,
+            None => return Err(PartialsError::Singular),
+        };
+
+        let partials_to = orbital_columns(&partials_to);
+        let mut stm = [[0.0; 6]; 6];
+        for i in 0..6 {
+            for j in 0..6 {
+                for k in 0..6 {
+                    stm[i][j] += partials_to[i][k] * inverse[k][j];
+                }
+            }
+        }
+
+        Ok(stm)
+    }
+
+    /// Derivative of the state with respect to a single element
+    fn element_derivative(&self, time: DateTime<Utc>, element: usize, step: f64) -> Result<[f64; 6], SGP4Error>
+    {
+        let value = self.elements[element];
+
+        // The eccentricity cannot be negative: use a forward difference close to 0
+        let low = if element == ECCENTRICITY && value - step < 0.0 {
+            value
+        } else {
+            value - step
+        };
+        let high = value + step;
+
+        let low_state = self.with_element(element, low)?.state(time)?;
+        let high_state = self.with_element(element, high)?.state(time)?;
+
+        let mut derivative = [0.0; 6];
+        for i in 0..6 {
+            derivative[i] = (high_state[i] - low_state[i]) / (high - low);
+        }
+
+        Ok(derivative)
+    }
+
+    /// Initialize a new propagator where one of the elements is changed
+    fn with_element(&self, element: usize, value: f64) -> Result<SGP4, SGP4Error>
+    {
+        let mut e = self.elements;
+        e[element] = value;
+
+        SGP4::new(self.mode,
+                  self.const_set,
+                  e[BSTAR],
+                  e[ECCENTRICITY],
+                  self.epoch,
+                  e[ARG_PERIGEE],
+                  e[INCLINATION],
+                  e[MEAN_ANOMALY],
+                  e[MEAN_MOTION],
+                  e[ASCENDING_NODE])
+    }
+
+    fn state(&self, time: DateTime<Utc>) -> Result<[f64; 6], SGP4Error>
+    {
+        let res = self.compute(time)?;
+        let r = res.position_vect();
+        let v = res.velocity_vect();
+
+        Ok([r[0], r[1], r[2], v[0], v[1], v[2]])
+    }
+}
+
+/// Keep the 6 columns of the orbital elements (drop B*)
+fn orbital_columns(partials: &[[f64; 7]; 6]) -> [[f64; 6]; 6]
+{
+    let mut m = [[0.0; 6]; 6];
+
+    for i in 0..6 {
+        m[i].copy_from_slice(&partials[i][0..6]);
+    }
+
+    m
+}
+
+/// Invert a 6x6 matrix with a Gauss-Jordan elimination (partial pivoting)
+fn invert(mut m: [[f64; 6]; 6]) -> Option<[[f64; 6]; 6]>
+{
+    let mut inv = [[0.0; 6]; 6];
+    for (i, row) in inv.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    // Pivots below this value are considered null
+    let scale = m.iter().flatten().fold(0.0_f64, |max, v| max.max(v.abs()));
+    let epsilon = scale * 1e-14;
+
+    for col in 0..6 {
+        let mut pivot = col;
+        for row in col + 1..6 {
+            if m[row][col].abs() > m[pivot][col].abs() {
+                pivot = row;
+            }
+        }
+
+        if m[pivot][col].abs() <= epsilon || !m[pivot][col].is_finite() {
+            return None;
+        }
+
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let p = m[col][col];
+        for j in 0..6 {
+            m[col][j] /= p;
+            inv[col][j] /= p;
+        }
+
+        for row in 0..6 {
+            if row != col {
+                let factor = m[row][col];
+                for j in 0..6 {
+                    m[row][j] -= factor * m[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+    }
+
+    Some(inv)
+}
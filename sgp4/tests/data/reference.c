@@ -0,0 +1,80 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Generate the reference output of the C implementation for the golden test of the backends.
+ *
+ * The record is initialized as the SGP4 wrapper does, with the WGS-72 constants and the epoch
+ * of the element set:
+ *
+ *   gcc -O0 -I../../src/c reference.c ../../src/c/sgp4.c -lm -o reference
+ *   ./reference < elements.txt > reference.txt
+ */
+
+#include <stdio.h>
+#include <string.h>
+#include "SGP4.h"
+
+bool sgp4init(char opsmode, ElsetRec *satrec);
+bool sgp4(ElsetRec *satrec, double tsince, double r[3], double v[3]);
+
+static const double DEG2RAD = pi / 180.0;
+
+static void run(char mode, const char *id, double epoch, const double *el, double start, double stop, double step)
+{
+    ElsetRec rec;
+    double r[3], v[3];
+    double t;
+    int yy = (int)(epoch / 1000.0);
+    int year = yy < 57 ? 2000 + yy : 1900 + yy;
+    double days = epoch - yy * 1000.0;
+
+    memset(&rec, 0, sizeof(rec));
+    rec.whichconst = wgs72;
+    jday(year, 1, 1, 0, 0, (days - 1.0) * 86400.0, &rec.jdsatepoch, &rec.jdsatepochF);
+    rec.bstar = el[0];
+    rec.ecco = el[1];
+    rec.argpo = el[2] * DEG2RAD;
+    rec.inclo = el[3] * DEG2RAD;
+    rec.mo = el[4] * DEG2RAD;
+    rec.no_kozai = el[5] * ((2.0 * pi) / 1440.0);
+    rec.nodeo = el[6] * DEG2RAD;
+
+    if (!sgp4init(mode, &rec)) {
+        printf("%c %s init error %d\n", mode, id, rec.error);
+        return;
+    }
+
+    for (t = start; t <= stop; t += step) {
+        if (sgp4(&rec, t, r, v))
+            printf("%c %s %g %.17g %.17g %.17g %.17g %.17g %.17g\n", mode, id, t,
+                   r[0], r[1], r[2], v[0], v[1], v[2]);
+        else
+            printf("%c %s %g error %d\n", mode, id, t, rec.error);
+    }
+}
+
+int main(void)
+{
+    char lines[64][256];
+    int count = 0, i, m;
+    const char modes[] = { 'a', 'i' };
+
+    while (count < 64 && fgets(lines[count], sizeof(lines[count]), stdin))
+        if (lines[count][0] != '#')
+            count++;
+
+    for (m = 0; m < 2; m++) {
+        for (i = 0; i < count; i++) {
+            char id[16];
+            double epoch, el[7], start, stop, step;
+
+            sscanf(lines[i], "%15s %lf %lf %lf %lf %lf %lf %lf %lf %lf %lf %lf", id, &epoch,
+                   &el[0], &el[1], &el[2], &el[3], &el[4], &el[5], &el[6], &start, &stop, &step);
+            run(modes[m], id, epoch, el, start, stop, step);
+        }
+    }
+
+    return 0;
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Partial derivatives and state transition matrix
+ *
+ * The partials are checked against finite differences computed here with a different step,
+ * and the state transition matrix between a time and itself must be the identity.
+ */
+
+use std::f64::consts::PI;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sgp4::sgp4::{ConstantsSet, OpsMode, PartialsSteps, SGP4};
+
+const DEG2RAD: f64 = PI / 180.0;
+
+/// Column of the mean anomaly in the partial derivatives
+const MEAN_ANOMALY: usize = 4;
+
+fn epoch() -> DateTime<Utc>
+{
+    Utc.with_ymd_and_hms(2008, 9, 20, 12, 25, 40).unwrap()
+}
+
+/// ISS (ZARYA) 08264.51782528, with the given mean anomaly [rad]
+fn iss(mean_anomaly: f64) -> SGP4
+{
+    SGP4::new(OpsMode::Improved, ConstantsSet::Set72,
+              -0.11606e-4,
+              0.0006703,
+              epoch(),
+              130.5360 * DEG2RAD,
+              51.6416 * DEG2RAD,
+              mean_anomaly,
+              15.72125391,
+              247.4627 * DEG2RAD).unwrap()
+}
+
+fn state(propagator: &SGP4, time: DateTime<Utc>) -> [f64; 6]
+{
+    let res = propagator.compute(time).unwrap();
+    let r = res.position_vect();
+    let v = res.velocity_vect();
+
+    [r[0], r[1], r[2], v[0], v[1], v[2]]
+}
+
+#[test]
+fn mean_anomaly_column()
+{
+    let mean_anomaly = 325.0288 * DEG2RAD;
+    let time = epoch() + Duration::minutes(200);
+    let partials = iss(mean_anomaly).partials(time, &PartialsSteps::default()).unwrap();
+
+    let step = 1e-4;
+    let high = state(&iss(mean_anomaly + step), time);
+    let low = state(&iss(mean_anomaly - step), time);
+
+    for row in 0..6 {
+        let expected = (high[row] - low[row]) / (2.0 * step);
+        let actual = partials[row][MEAN_ANOMALY];
+
+        // About 6800 km/rad on the positions and 7.7 km/s/rad on the velocities
+        assert!((actual - expected).abs() < 1e-5 * expected.abs().max(1.0), "row {}: {} != {}", row, actual, expected);
+    }
+}
+
+#[test]
+fn transition_to_the_same_time()
+{
+    let time = epoch() + Duration::minutes(200);
+    let stm = iss(325.0288 * DEG2RAD).state_transition_matrix(time, time, &PartialsSteps::default()).unwrap();
+
+    for (i, row) in stm.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+
+            // Mixed units: positions [km] and velocities [km/s]
+            assert!((value - expected).abs() < 1e-6, "[{}][{}]: {}", i, j, value);
+        }
+    }
+}
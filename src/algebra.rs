@@ -3,6 +3,9 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
+use chrono::Duration;
+use crate::Result;
+
 pub fn evalpoly(val: f64, coeffs: Vec<f64>) -> f64
 {
     let mut ret: f64 = 0.0;
@@ -14,4 +17,30 @@ pub fn evalpoly(val: f64, coeffs: Vec<f64>) -> f64
     }
 
     ret
-}
\ No newline at end of file
+}
+
+/// Length of a duration [s]
+pub fn seconds(d: Duration) -> f64
+{
+    match d.num_nanoseconds() {
+        Some(ns) => ns as f64 / 1e9,
+        None => d.num_milliseconds() as f64 / 1e3,
+    }
+}
+
+/// Root of f in [a, b] within the given tolerance, f(a) and f(b) having opposite signs
+pub fn bisect<F: Fn(f64) -> Result<f64>>(f: &F, mut a: f64, mut b: f64, fa: f64, tolerance: f64) -> Result<f64>
+{
+    let negative_at_a = fa < 0.0;
+
+    while b - a > tolerance {
+        let m = (a + b) / 2.0;
+        if (f(m)? < 0.0) == negative_at_a {
+            a = m;
+        } else {
+            b = m;
+        }
+    }
+
+    Ok((a + b) / 2.0)
+}
@@ -4,4 +4,11 @@
  */
 
 
-pub const EARTH_EQUATORIAL_RADIUS_KM: f64 = 6371.3;
\ No newline at end of file
+/// Mean radius of the Earth [km]. See `ellipsoid` for the shape of the Earth
+pub const EARTH_MEAN_RADIUS_KM: f64 = 6371.0;
+
+/// Nominal angular velocity of the Earth [rad/s]
+pub const EARTH_ROTATION_RATE: f64 = 7.292115146706979e-5;
+
+/// Speed of light in vacuum [km/s]
+pub const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
@@ -0,0 +1,280 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Shadow of the Earth
+ *
+ * Cylindrical model: the shadow is a cylinder of the radius of the Earth behind it, the rays
+ * of the Sun being parallel.
+ *
+ * Conical model: the discs of the Sun and of the Earth are compared as seen from the object
+ * (See Montenbruck & Gill, Satellite Orbits, 3.4.2). With a the apparent radius of the Sun, b
+ * the one of the Earth and c the angle between their centers, the object is
+ *  - in the umbra if c < b - a (the Earth hides the whole Sun),
+ *  - in the penumbra if c < a + b (the Earth hides a part of the Sun),
+ *  - lit otherwise.
+ * The illuminated fraction is 1 minus the part of the disc of the Sun hidden by the Earth.
+ * The Earth looks smaller than the Sun (antumbra) only beyond 1.4 million km, where the
+ * object is counted in the penumbra.
+ *
+ * The shadow events of an object are the roots of c - (a + b) and c - (b - a), sampled over
+ * the time window and refined by bisection.
+ */
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+use chrono::Duration;
+use crate::algebra::{bisect, seconds};
+use crate::ellipsoid::Ellipsoid;
+use crate::frames::GCRF;
+use crate::geometry::Vector;
+use crate::sun;
+use crate::time::Epoch;
+use crate::traits::{Framable, Observable};
+use crate::utils::Observer;
+use crate::Result;
+
+/// Accuracy of the event times [s]
+const TIME_TOLERANCE: f64 = 0.01;
+
+/// # Model of the shadow of the Earth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowModel {
+    /// Parallel rays of the Sun, no penumbra
+    Cylindrical,
+    /// Sun of finite size, with umbra and penumbra
+    Conical,
+}
+
+/// # Shadow of the Earth at the position of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shadow {
+    Sunlit,
+    Penumbra,
+    Umbra,
+}
+
+/// # Kind of shadow event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowEventKind {
+    PenumbraEntry,
+    UmbraEntry,
+    UmbraExit,
+    PenumbraExit,
+}
+
+/// # Time where an object enters or leaves the shadow of the Earth
+#[derive(Clone, Copy)]
+pub struct ShadowEvent {
+    pub time: Epoch,
+    pub kind: ShadowEventKind,
+}
+
+/// Distance [km] to the border of the cylindrical shadow, negative inside
+fn cylindrical_margin(position: [f64; 3], epoch: &Epoch) -> f64
+{
+    let s = Vector::from_tuple(sun::position_gcrf(epoch));
+    let r = Vector::from_tuple(position);
+    let distance = s.length();
+    let radius = Ellipsoid::WGS84.equatorial_radius;
+
+    // Projection of the position on the Earth-Sun axis
+    let along = (&r * &s) / distance;
+    if along >= 0.0 {
+        return r.length() - radius;
+    }
+
+    Vector::from_tuple([0, 1, 2].map(|i| r[i] - along * s[i] / distance)).length() - radius
+}
+
+/// Apparent radii of the Sun and of the Earth seen from the position, and angle between their
+/// centers [rad]
+fn discs(position: [f64; 3], epoch: &Epoch) -> (f64, f64, f64)
+{
+    let s = sun::position_gcrf(epoch);
+    let to_sun = Vector::from_tuple([0, 1, 2].map(|i| s[i] - position[i]));
+    let to_earth = Vector::from_tuple(position.map(|x| -x));
+
+    let a = (sun::SUN_RADIUS_KM as f64 / to_sun.length()).asin();
+    let b = (Ellipsoid::WGS84.equatorial_radius / to_earth.length()).min(1.0).asin();
+
+    (a, b, to_sun.angle(&to_earth).radians())
+}
+
+/// Shadow of the Earth at the given GCRF position [km]
+pub fn shadow(position: [f64; 3], epoch: &Epoch, model: ShadowModel) -> Shadow
+{
+    match model {
+        ShadowModel::Cylindrical => {
+            if cylindrical_margin(position, epoch) > 0.0 {
+                Shadow::Sunlit
+            } else {
+                Shadow::Umbra
+            }
+        },
+        ShadowModel::Conical => {
+            let (a, b, c) = discs(position, epoch);
+
+            if c >= a + b {
+                Shadow::Sunlit
+            } else if c < b - a {
+                Shadow::Umbra
+            } else {
+                Shadow::Penumbra
+            }
+        },
+    }
+}
+
+/// Fraction of the disc of the Sun visible from the given GCRF position [km], from 0 in the
+/// umbra to 1 when fully lit
+pub fn illumination(position: [f64; 3], epoch: &Epoch, model: ShadowModel) -> f64
+{
+    if model == ShadowModel::Cylindrical {
+        return match shadow(position, epoch, model) {
+            Shadow::Sunlit => 1.0,
+            _ => 0.0,
+        };
+    }
+
+    let (a, b, c) = discs(position, epoch);
+
+    if c >= a + b {
+        1.0
+    } else if c < b - a {
+        0.0
+    } else if c < a - b {
+        // The whole Earth is in front of the Sun
+        1.0 - (b * b) / (a * a)
+    } else {
+        // Area of the overlap of the 2 discs
+        let x = (c * c + a * a - b * b) / (2.0 * c);
+        let y = (a * a - x * x).max(0.0).sqrt();
+        let hidden = a * a * (x / a).clamp(-1.0, 1.0).acos() + b * b * ((c - x) / b).clamp(-1.0, 1.0).acos() - c * y;
+
+        (1.0 - hidden / (PI * a * a)).clamp(0.0, 1.0)
+    }
+}
+
+/// Return true if the object at the given GCRF position [km] is lit by the Sun, even partly:
+/// the object is only dark in the umbra
+pub fn is_sunlit(position: [f64; 3], epoch: &Epoch, model: ShadowModel) -> bool
+{
+    shadow(position, epoch, model) != Shadow::Umbra
+}
+
+/// # Search of the times where an object enters and leaves the shadow of the Earth
+pub struct EclipsePredictor<'a> {
+    observable: &'a dyn Observable,
+    model: ShadowModel,
+    step: Duration,
+}
+
+impl<'a> EclipsePredictor<'a> {
+    /// Conical shadow, sampled every 30 seconds (suited to LEO satellites)
+    pub fn new(observable: &'a dyn Observable) -> EclipsePredictor<'a>
+    {
+        EclipsePredictor {
+            observable,
+            model: ShadowModel::Conical,
+            step: Duration::seconds(30),
+        }
+    }
+
+    pub fn with_model(mut self, model: ShadowModel) -> EclipsePredictor<'a>
+    {
+        self.model = model;
+        self
+    }
+
+    /// Sampling step of the shadow. Crossings of the shadow shorter than the step may be
+    /// missed.
+    pub fn with_step(mut self, step: Duration) -> EclipsePredictor<'a>
+    {
+        self.step = step;
+        self
+    }
+
+    /// GCRF position of the object [km]
+    fn position(&self, time: Epoch) -> Result<[f64; 3]>
+    {
+        let mut observation = self.observable.observation_at(&Observer::earth(), time)?;
+        observation.state.change_frame(Rc::new(GCRF::new(time)))?;
+        let p = observation.state.position();
+
+        Ok([p[0], p[1], p[2]])
+    }
+
+    /// Shadow of the Earth at the position of the object
+    pub fn shadow(&self, time: Epoch) -> Result<Shadow>
+    {
+        Ok(shadow(self.position(time)?, &time, self.model))
+    }
+
+    /// Fraction of the disc of the Sun visible from the object
+    pub fn illumination(&self, time: Epoch) -> Result<f64>
+    {
+        Ok(illumination(self.position(time)?, &time, self.model))
+    }
+
+    /// Shadow events over the time window, sorted by time
+    pub fn events(&self, start: Epoch, end: Epoch) -> Result<Vec<ShadowEvent>>
+    {
+        let duration = seconds(end - start);
+        let step = seconds(self.step);
+        let at = |t: f64| start + Duration::nanoseconds((t * 1e9).round() as i64);
+
+        // Margins to the borders of the shadows, negative inside: (penumbra, umbra)
+        let margins = |t: f64| -> Result<(f64, f64)> {
+            let time = at(t);
+            let position = self.position(time)?;
+
+            match self.model {
+                ShadowModel::Cylindrical => {
+                    let margin = cylindrical_margin(position, &time);
+                    Ok((margin, margin))
+                },
+                ShadowModel::Conical => {
+                    let (a, b, c) = discs(position, &time);
+                    Ok((c - (a + b), c - (b - a)))
+                },
+            }
+        };
+        let penumbra = |t: f64| -> Result<f64> { Ok(margins(t)?.0) };
+        let umbra = |t: f64| -> Result<f64> { Ok(margins(t)?.1) };
+
+        let mut samples = Vec::new();
+        let mut t = 0.0;
+        while t < duration {
+            samples.push((t, margins(t)?));
+            t += step;
+        }
+        samples.push((duration, margins(duration)?));
+
+        let mut events = Vec::new();
+        for i in 1..samples.len() {
+            let (t0, (p0, u0)) = samples[i - 1];
+            let (t1, (p1, u1)) = samples[i];
+
+            if self.model == ShadowModel::Conical && (p0 < 0.0) != (p1 < 0.0) {
+                let kind = if p1 < 0.0 { ShadowEventKind::PenumbraEntry } else { ShadowEventKind::PenumbraExit };
+                events.push((bisect(&penumbra, t0, t1, p0, TIME_TOLERANCE)?, kind));
+            }
+
+            if (u0 < 0.0) != (u1 < 0.0) {
+                let kind = if u1 < 0.0 { ShadowEventKind::UmbraEntry } else { ShadowEventKind::UmbraExit };
+                events.push((bisect(&umbra, t0, t1, u0, TIME_TOLERANCE)?, kind));
+            }
+        }
+
+        events.sort_by(|e1, e2| e1.0.total_cmp(&e2.0));
+
+        Ok(events.into_iter()
+            .map(|(t, kind)| ShadowEvent {
+                time: at(t),
+                kind,
+            })
+            .collect())
+    }
+}
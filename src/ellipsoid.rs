@@ -0,0 +1,109 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Reference ellipsoids of the Earth
+ *
+ * Conversions between geodetic coordinates (latitude, longitude, altitude above the
+ * ellipsoid) and Earth-fixed cartesian coordinates.
+ */
+
+/// # Ellipsoid of revolution modelling the shape of the Earth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    /// Equatorial radius [km]
+    pub equatorial_radius: f64,
+    pub flattening: f64,
+}
+
+impl Ellipsoid {
+    /// World Geodetic System 1984, used by GPS
+    pub const WGS84: Ellipsoid = Ellipsoid {
+        equatorial_radius: 6378.137,
+        flattening: 1.0 / 298.257223563,
+    };
+
+    /// World Geodetic System 1972, used by SGP4
+    pub const WGS72: Ellipsoid = Ellipsoid {
+        equatorial_radius: 6378.135,
+        flattening: 1.0 / 298.26,
+    };
+
+    /// Geodetic Reference System 1980, used by ITRF
+    pub const GRS80: Ellipsoid = Ellipsoid {
+        equatorial_radius: 6378.137,
+        flattening: 1.0 / 298.257222101,
+    };
+
+    pub fn new(equatorial_radius: f64, flattening: f64) -> Ellipsoid
+    {
+        Ellipsoid {
+            equatorial_radius,
+            flattening,
+        }
+    }
+
+    /// Square of the first eccentricity
+    pub fn eccentricity_squared(&self) -> f64
+    {
+        self.flattening * (2.0 - self.flattening)
+    }
+
+    /// Cartesian coordinates [km] of a point given by its geodetic latitude and longitude [rad]
+    /// and its altitude above the ellipsoid [km]
+    pub fn to_cartesian(&self, latitude: f64, longitude: f64, altitude: f64) -> [f64; 3]
+    {
+        let e2 = self.eccentricity_squared();
+        let (slat, clat) = latitude.sin_cos();
+        let (slon, clon) = longitude.sin_cos();
+
+        // Radius of curvature in the prime vertical
+        let n = self.equatorial_radius / (1.0 - e2 * slat * slat).sqrt();
+
+        [
+            (n + altitude) * clat * clon,
+            (n + altitude) * clat * slon,
+            (n * (1.0 - e2) + altitude) * slat,
+        ]
+    }
+
+    /// Geodetic latitude and longitude [rad] and altitude [km] of a point given by its
+    /// cartesian coordinates [km].
+    ///
+    /// Closed form solution, exact for all the points further than about 43 km from the center
+    /// of the Earth (See H. Vermeille, Direct transformation from geocentric coordinates to
+    /// geodetic coordinates, Journal of Geodesy 76, 2002)
+    pub fn to_geodetic(&self, position: [f64; 3]) -> (f64, f64, f64)
+    {
+        let a = self.equatorial_radius;
+        let e2 = self.eccentricity_squared();
+        let e4 = e2 * e2;
+        let [x, y, z] = position;
+        let rho = x.hypot(y);
+
+        let p = rho * rho / (a * a);
+        let q = (1.0 - e2) * z * z / (a * a);
+        let r = (p + q - e4) / 6.0;
+        let s = e4 * p * q / (4.0 * r * r * r);
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r * (1.0 + t + 1.0 / t);
+        let v = (u * u + e4 * q).sqrt();
+        let w = e2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w * w).sqrt() - w;
+        let d = k * rho / (k + e2);
+        let dz = d.hypot(z);
+
+        let latitude = 2.0 * z.atan2(d + dz);
+        let longitude = y.atan2(x);
+        let altitude = (k + e2 - 1.0) / k * dz;
+
+        (latitude, longitude, altitude)
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Ellipsoid::WGS84
+    }
+}
@@ -0,0 +1,318 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Earth Orientation Parameters
+ *
+ * The irregularities of the Earth rotation cannot be predicted by a model and are published
+ * by the IERS:
+ *  - finals.all / finals2000A.all (https://datacenter.iers.org/products/eop/rapid/standard/)
+ *  - EOP 14 C04 / 20 C04 (https://hpiers.obspm.fr/iers/eop/eopc04/)
+ *
+ * The values are linearly interpolated between the daily entries. The celestial pole offsets
+ * of the IAU 1980 nutation (finals.all) and of the IAU 2006/2000A model (finals2000A.all, C04)
+ * are converted into each other by matching the celestial poles of both reductions.
+ *
+ * When no table is loaded, all the parameters are 0, which is an error of up to 0.9s on UT1
+ * and about 10m on the Earth-fixed positions.
+ */
+
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use crate::frames::{celestial_pole, nutation_corrections};
+use crate::iau2006;
+use crate::time::{tai_minus_utc, Epoch, TimeScale};
+use crate::{Error, Result};
+
+const MJD_OFFSET: f64 = 2400000.5;
+const ARCSEC_TO_RAD: f64 = PI / 648000.0;
+const MILLIARCSEC_TO_RAD: f64 = PI / 648000000.0;
+
+/// Table installed with `set_earth_orientation`
+static EARTH_ORIENTATION: RwLock<Option<EarthOrientation>> = RwLock::new(None);
+
+/// # Format of an EOP file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EopFormat {
+    /// IERS finals.all / finals.data: celestial pole offsets for the IAU 1980 nutation (dPsi, dEps)
+    Finals,
+    /// IERS finals2000A.all / finals2000A.data: celestial pole offsets for the IAU 2000A
+    /// nutation (dX, dY)
+    Finals2000A,
+    /// IERS EOP 14 C04 or 20 C04: celestial pole offsets for the IAU 2000A nutation (dX, dY)
+    C04,
+}
+
+/// # Earth Orientation Parameters at a given time
+/// The angles are in radians. The celestial pole offsets of the model which is not given by
+/// the loaded file are derived from the other ones, so that the FK5 and the CIO based
+/// reductions use the same observed pole.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EopValues {
+    /// Polar motion
+    pub x_p: f64,
+    pub y_p: f64,
+    /// UT1 - UTC [s]
+    pub ut1_utc: f64,
+    /// Excess length of day [s]
+    pub lod: f64,
+    /// Celestial pole offsets for the IAU 1980 nutation (used by TEME and the FK5 frames)
+    pub dpsi: f64,
+    pub deps: f64,
+    /// Celestial pole offsets for the IAU 2000A nutation (used by the CIO based frames)
+    pub dx: f64,
+    pub dy: f64,
+}
+
+/// Daily entry of the table. UT1 is stored as UT1 - TAI to interpolate across leap seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entry {
+    mjd: f64,
+    values: EopValues,
+    ut1_tai: f64,
+}
+
+/// # Table of Earth Orientation Parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarthOrientation {
+    entries: Vec<Entry>,
+}
+
+impl EarthOrientation {
+    /// Load an IERS EOP file
+    pub fn from_file<P: AsRef<Path>>(path: P, format: EopFormat) -> Result<EarthOrientation>
+    {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        EarthOrientation::parse(&content, format)
+    }
+
+    /// Parse the content of an IERS EOP file
+    pub fn parse(content: &str, format: EopFormat) -> Result<EarthOrientation>
+    {
+        let mut values = Vec::new();
+
+        for line in content.lines() {
+            let parsed = match format {
+                EopFormat::Finals => parse_finals_line(line, false)?,
+                EopFormat::Finals2000A => parse_finals_line(line, true)?,
+                EopFormat::C04 => parse_c04_line(line)?,
+            };
+
+            if let Some(v) = parsed {
+                values.push(v);
+            }
+        }
+
+        if values.is_empty() {
+            return Err(Error::Parse(String::from("The EOP file has no entry")));
+        }
+
+        if values.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(Error::Parse(String::from("The EOP file is not sorted by date")));
+        }
+
+        let entries = values.into_iter().map(|(mjd, values)| {
+            Entry {
+                mjd,
+                values,
+                ut1_tai: values.ut1_utc - tai_minus_utc(mjd + MJD_OFFSET),
+            }
+        }).collect();
+
+        Ok(EarthOrientation {
+            entries
+        })
+    }
+
+    /// Interpolate the parameters at the given UTC Julian date.
+    ///
+    /// Outside of the table, the first or last entry is used.
+    pub fn at(&self, jd_utc: f64) -> EopValues
+    {
+        let mjd = jd_utc - MJD_OFFSET;
+
+        let next = self.entries.partition_point(|e| e.mjd <= mjd);
+        if next == 0 {
+            return self.entries[0].values;
+        }
+        if next == self.entries.len() {
+            return self.entries[next - 1].values;
+        }
+
+        let a = &self.entries[next - 1];
+        let b = &self.entries[next];
+        let t = (mjd - a.mjd) / (b.mjd - a.mjd);
+        let lerp = |va: f64, vb: f64| va + (vb - va) * t;
+
+        EopValues {
+            x_p: lerp(a.values.x_p, b.values.x_p),
+            y_p: lerp(a.values.y_p, b.values.y_p),
+            ut1_utc: lerp(a.ut1_tai, b.ut1_tai) + tai_minus_utc(jd_utc),
+            lod: lerp(a.values.lod, b.values.lod),
+            dpsi: lerp(a.values.dpsi, b.values.dpsi),
+            deps: lerp(a.values.deps, b.values.deps),
+            dx: lerp(a.values.dx, b.values.dx),
+            dy: lerp(a.values.dy, b.values.dy),
+        }
+    }
+
+    /// UTC Julian dates of the first and last entries
+    pub fn range(&self) -> (f64, f64)
+    {
+        (self.entries[0].mjd + MJD_OFFSET, self.entries[self.entries.len() - 1].mjd + MJD_OFFSET)
+    }
+}
+
+/// Use the given table for the time scale and frame conversions
+pub fn set_earth_orientation(table: EarthOrientation)
+{
+    let mut installed = EARTH_ORIENTATION.write().unwrap_or_else(|e| e.into_inner());
+    *installed = Some(table);
+}
+
+/// Remove the table installed with `set_earth_orientation`: all the parameters are 0
+pub fn clear_earth_orientation()
+{
+    let mut installed = EARTH_ORIENTATION.write().unwrap_or_else(|e| e.into_inner());
+    *installed = None;
+}
+
+/// Earth Orientation Parameters at the given UTC Julian date, with the table currently used
+pub fn eop_at(jd_utc: f64) -> EopValues
+{
+    let installed = EARTH_ORIENTATION.read().unwrap_or_else(|e| e.into_inner());
+
+    match &*installed {
+        Some(table) => table.at(jd_utc),
+        None => EopValues::default(),
+    }
+}
+
+/// Parse a fixed-width field of a finals line. Blank fields are missing values.
+fn finals_field(line: &str, start: usize, end: usize) -> Result<Option<f64>>
+{
+    let field = match line.get(start..end.min(line.len())) {
+        Some(f) => f.trim(),
+        None => return Ok(None),
+    };
+
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    match field.parse::<f64>() {
+        Ok(v) => Ok(Some(v)),
+        Err(_) => Err(Error::Parse(format!("Invalid number '{}' in EOP line: {}", field, line))),
+    }
+}
+
+/// finals.all and finals2000A.all share the same columns (See readme.finals2000A).
+/// The lines without polar motion or UT1 (far predictions) are skipped.
+fn parse_finals_line(line: &str, iau2000: bool) -> Result<Option<(f64, EopValues)>>
+{
+    let mjd = finals_field(line, 7, 15)?;
+    let x_p = finals_field(line, 18, 27)?;
+    let y_p = finals_field(line, 37, 46)?;
+    let ut1_utc = finals_field(line, 58, 68)?;
+
+    let (mjd, x_p, y_p, ut1_utc) = match (mjd, x_p, y_p, ut1_utc) {
+        (Some(mjd), Some(x_p), Some(y_p), Some(ut1_utc)) => (mjd, x_p, y_p, ut1_utc),
+        _ => return Ok(None),
+    };
+
+    let lod = finals_field(line, 79, 86)?.unwrap_or(0.0);
+    let pole = match (finals_field(line, 97, 106)?, finals_field(line, 116, 125)?) {
+        (Some(pole_1), Some(pole_2)) => Some((pole_1 * MILLIARCSEC_TO_RAD, pole_2 * MILLIARCSEC_TO_RAD)),
+        _ => None,
+    };
+
+    let mut values = EopValues {
+        x_p: x_p * ARCSEC_TO_RAD,
+        y_p: y_p * ARCSEC_TO_RAD,
+        ut1_utc,
+        lod: lod / 1000.0,
+        ..EopValues::default()
+    };
+
+    // Without the IAU 1980 offsets, the IAU 2006/2000A pole is closer to the observed one
+    if iau2000 {
+        let (dx, dy) = pole.unwrap_or((0.0, 0.0));
+        set_cip_offsets(&mut values, mjd, dx, dy);
+    } else if let Some((dpsi, deps)) = pole {
+        set_nutation_corrections(&mut values, mjd, dpsi, deps);
+    }
+
+    Ok(Some((mjd, values)))
+}
+
+/// EOP 14 C04: year month day MJD x y UT1-UTC LOD dX dY ...
+/// EOP 20 C04: year month day hour MJD x y UT1-UTC dX dY xrt yrt LOD ...
+/// The angles are in arcseconds. The header lines are skipped.
+fn parse_c04_line(line: &str) -> Result<Option<(f64, EopValues)>>
+{
+    let line = line.trim();
+    if !line.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let mut fields = Vec::new();
+    for field in line.split_whitespace() {
+        match field.parse::<f64>() {
+            Ok(v) => fields.push(v),
+            Err(_) => return Err(Error::Parse(format!("Invalid number '{}' in EOP line: {}", field, line))),
+        }
+    }
+
+    // The 4th column is the MJD in 14 C04 and the hour in 20 C04
+    let (mjd, x_p, y_p, ut1_utc, lod, dx, dy) = if fields.len() >= 10 && fields[3] > 24.0 {
+        (fields[3], fields[4], fields[5], fields[6], fields[7], fields[8], fields[9])
+    } else if fields.len() >= 13 {
+        (fields[4], fields[5], fields[6], fields[7], fields[12], fields[8], fields[9])
+    } else {
+        return Err(Error::Parse(format!("Invalid EOP line: {}", line)));
+    };
+
+    let mut values = EopValues {
+        x_p: x_p * ARCSEC_TO_RAD,
+        y_p: y_p * ARCSEC_TO_RAD,
+        ut1_utc,
+        lod,
+        ..EopValues::default()
+    };
+    set_cip_offsets(&mut values, mjd, dx * ARCSEC_TO_RAD, dy * ARCSEC_TO_RAD);
+
+    Ok(Some((mjd, values)))
+}
+
+/// Set the offsets of the CIP of the IAU 2006/2000A model, and the corrections to the IAU 1980
+/// nutation which move the pole of the FK5 reduction to the same observed pole
+fn set_cip_offsets(values: &mut EopValues, mjd: f64, dx: f64, dy: f64)
+{
+    let epoch = Epoch::from_julian_day(mjd + MJD_OFFSET, TimeScale::UTC);
+    let (x, y) = iau2006::cip_xy(epoch.julian_day_parts(TimeScale::TT));
+
+    values.dx = dx;
+    values.dy = dy;
+    (values.dpsi, values.deps) = nutation_corrections(&epoch, x + dx, y + dy);
+}
+
+/// Set the corrections to the IAU 1980 nutation, and the offsets of the CIP of the IAU
+/// 2006/2000A model which give the same observed pole
+fn set_nutation_corrections(values: &mut EopValues, mjd: f64, dpsi: f64, deps: f64)
+{
+    let epoch = Epoch::from_julian_day(mjd + MJD_OFFSET, TimeScale::UTC);
+    let (x, y) = iau2006::cip_xy(epoch.julian_day_parts(TimeScale::TT));
+    let (x_fk5, y_fk5) = celestial_pole(&epoch, dpsi, deps);
+
+    values.dpsi = dpsi;
+    values.deps = deps;
+    values.dx = x_fk5 - x;
+    values.dy = y_fk5 - y;
+}
@@ -1 +1,67 @@
-pub type BoxResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+use std::{error, fmt};
+use sgp4::sgp4::{PartialsError, SGP4Error};
+
+///
+/// # Represent an error returned by tardis
+///
+#[derive(Debug)]
+pub enum Error {
+    /// The SGP4 propagation failed. The underlying error tells if the satellite has decayed or
+    /// if its elements are invalid.
+    Propagation(SGP4Error),
+    /// The input (e.g. a TLE) could not be parsed
+    Parse(String),
+    /// A frame transformation could not be computed
+    Frame(String),
+    /// A mathematical operation is not possible (e.g. inverting a singular matrix)
+    Math(String),
+    /// A data file (e.g. leap seconds) could not be read
+    Io(std::io::Error),
+    /// An ephemeris does not cover the requested body or epoch
+    Ephemeris(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Propagation(e) => write!(f, "Propagation error: {}", e),
+            Error::Parse(s) => write!(f, "Parse error: {}", s),
+            Error::Frame(s) => write!(f, "Frame error: {}", s),
+            Error::Math(s) => write!(f, "Math error: {}", s),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Ephemeris(s) => write!(f, "Ephemeris error: {}", s),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Propagation(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SGP4Error> for Error {
+    fn from(e: SGP4Error) -> Self {
+        Error::Propagation(e)
+    }
+}
+
+impl From<PartialsError> for Error {
+    fn from(e: PartialsError) -> Self {
+        match e {
+            PartialsError::Propagation(e) => Error::Propagation(e),
+            PartialsError::Singular => Error::Math(e.to_string()),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -3,186 +3,232 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-use std::f64::consts::PI;
+use crate::constants::EARTH_ROTATION_RATE;
 use crate::traits::Frame;
-use crate::geometry::{
-    Matrix,
-    RotationAxis,
-};
-use crate::time::jd_utc_to_tt;
-use chrono::{
-    DateTime,
-    Timelike,
-    Utc
-};
-use sgp4::sgp4::SGP4;
-use crate::{kf5, time};
-use crate::algebra::evalpoly;
+use crate::geometry::Matrix;
+use crate::eop::eop_at;
+use crate::spk;
+use crate::sun;
+use crate::Result;
+use crate::time::{Epoch, TimeScale};
+
+mod cio;
+mod fk5;
+mod graph;
+mod topocentric;
+pub use cio::{CIRS, TIRS, ITRS};
+pub use fk5::{MOD, TOD, PEF, J2000};
+pub(crate) use fk5::{celestial_pole, ecliptic_to_mod, gcrf_to_mod, nutation_corrections};
+pub use graph::*;
+pub use crate::iau2006::{clear_nutation_series, set_nutation_series, NutationSeries};
+pub use topocentric::{SEZ, ENU, NED};
+
+/// Graph of the frames provided by tardis
+///
+/// ```text
+///        J2000  ICRS  HCRS
+///            \   |   /
+///    ITRS      GCRF --- MOD --- TOD --- TEME --- PEF --- ITRF
+///      |        |
+///    TIRS --- CIRS
+/// ```
+///
+/// The topocentric frames (SEZ, ENU, NED) are attached to ITRF.
+fn builtin_graph() -> TransformGraph
+{
+    let mut graph = TransformGraph::new();
+
+    graph.add_epoch_independent("GCRF");
+    graph.add_epoch_independent("J2000");
+    graph.add_epoch_independent("ICRS");
+
+    graph.add_transform("GCRF", "J2000", TransformFn::Fixed(|| Transform::rotation(fk5::gcrf_to_j2000())));
+    graph.add_transform("GCRF", "HCRS", TransformFn::Timed(gcrf_to_hcrs));
+    graph.add_transform("GCRF", "ICRS", TransformFn::Fallible(gcrf_to_icrs));
+
+    graph.add_transform("GCRF", "MOD", TransformFn::Timed(|e| Transform::rotation(fk5::gcrf_to_mod(e))));
+    graph.add_transform("MOD", "TOD", TransformFn::Timed(|e| Transform::rotation(fk5::mod_to_tod(e))));
+    graph.add_transform("TOD", "TEME", TransformFn::Timed(|e| Transform::rotation(fk5::tod_to_teme(e))));
+    graph.add_transform("TEME", "PEF", TransformFn::Timed(|e| Transform::rotating(fk5::teme_to_pef(e), earth_angular_velocity(e))));
+    graph.add_transform("PEF", "ITRF", TransformFn::Timed(|e| Transform::rotation(pef_to_itrf(e))));
+
+    graph.add_transform("GCRF", "CIRS", TransformFn::Timed(|e| Transform::rotation(cio::gcrs_to_cirs(e))));
+    graph.add_transform("CIRS", "TIRS", TransformFn::Timed(|e| Transform::rotating(cio::cirs_to_tirs(e), earth_angular_velocity(e))));
+    graph.add_transform("TIRS", "ITRS", TransformFn::Timed(|e| Transform::rotation(cio::tirs_to_itrs(e))));
+
+    graph
+}
 
+/// Angular velocity of the Earth [rad/s], corrected with the excess length of day of the EOP
+/// table. The rates of the precession, the nutation and the polar motion are neglected.
+fn earth_angular_velocity(epoch: &Epoch) -> [f64; 3]
+{
+    let lod = eop_at(epoch.julian_day(TimeScale::UTC)).lod;
+
+    [0.0, 0.0, EARTH_ROTATION_RATE * (1.0 - lod / 86400.0)]
+}
 
 ///
 /// TEME is the frame used by TLE and sgp4. Note that the frame is dependant on time
 ///
+#[derive(Clone, Copy)]
 pub struct TEME {
-    date_time: DateTime<Utc>,
+    epoch: Epoch,
 }
 
 impl TEME {
-    pub(crate) fn new(date_time: DateTime<Utc>) -> TEME {
+    pub fn new(epoch: Epoch) -> TEME {
         TEME {
-            date_time
+            epoch
         }
     }
+}
 
-    fn mod_to_gcrf_fk5(tt: f64) -> Matrix {
-        let precession = kf5::precession(tt);
-        Matrix::rot_from_angles(precession[2], -precession[1], precession[0], RotationAxis::ZYZ)
-    }
-
-    fn teme_to_mod(tt: f64, delta_eps: f64, delta_psi: f64) -> Matrix {
-
-        // Compute the nutation in the Julian Day (Terrestrial Time) `JD_TT`.
-        let nutation = kf5::nutation(tt);
-
-        // Add the corrections to the nutation in obliquity and longitude.
-        let a = nutation[0];
-        let b = nutation[1] + delta_eps;
-        let c = nutation[2] + delta_psi;
-
-        let obliquity = a + b;
-
-        // Evaluate the Delaunay parameters associated with the Moon in the interval
-        // [0,2π]°.
-        //
-        // The parameters here were updated as stated in the errata [2].
-        let t_tt = (tt - time::JD_J2000) / 36525.0;
-        let r = 360.0;
-        let mut delaunay = evalpoly(
-            t_tt,
-            vec![125.04452222,
-                 -5.0 * r - 134.1362608,
-                 0.0020708,
-                 2.2e-6],
-        );
-        delaunay = (delaunay % 360.0) * PI / 180.0;
-
-        // Compute the equation of Equinoxes.
-        //
-        // According to [2], the constant unit before `sin(2Ω_m)` is also in [rad].
-        let Eq_equinox1982 = c * a.cos() +
-            (0.002640 * delaunay.sin() + 0.000063 * (2.0 * delaunay).sin()) * PI / 648000.0;
-
-        // Compute the rotation.
-        let tod_teme = Matrix::rot_from_angles(-Eq_equinox1982, 0.0, 0.0, RotationAxis::ZYX);
-        let mod_tod = Matrix::rot_from_angles(obliquity, c, -a, RotationAxis::XZX);
-
-        Matrix::compose(mod_tod, tod_teme)
+impl Frame for TEME {
+    fn name(&self) -> String {
+        String::from("TEME")
     }
 
-    fn gcrf_to_teme_matrix(&self) -> Matrix {
-        self.teme_to_gcrf_matrix().invert().unwrap()
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
     }
+}
 
-    fn teme_to_gcrf_matrix(&self) -> Matrix {
-        //let milliarcsec_to_rad = PI / 648000000.0;
-
-        // Get the time in TT.
-        let jd_tt = jd_utc_to_tt(SGP4::julian_day(self.date_time));
-
-        // Get the EOP data related to the desired epoch.
-        // This is currently not supported. TODO: Find a table with this information
-        // Because of this missing information, GCRF is closer to J2000
-        let eps_1980 = 0.0;//eop_data.dEps(jd_tt) * milliarcsec_to_rad;
-        let psi_1980 = 0.0;//eop_data.dPsi(jd_tt) * milliarcsec_to_rad;
-
-        // Return the rotation.
-        let r_TEME_MOD = TEME::teme_to_mod(jd_tt, eps_1980, psi_1980);
-        let r_MOD_GCRF = TEME::mod_to_gcrf_fk5(jd_tt);
 
-        // Compose the full rotation.
-        Matrix::compose(r_MOD_GCRF, r_TEME_MOD)
+#[derive(Clone, Copy)]
+pub struct GCRF {
+    epoch: Epoch,
+}
+impl GCRF {
+    pub fn new(epoch: Epoch) -> GCRF {
+        GCRF {
+            epoch
+        }
     }
 }
 
-impl Frame for TEME {
+impl Frame for GCRF {
     fn name(&self) -> String {
-        String::from("TEME")
+        String::from("GCRF")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
     }
+}
 
+/// # Heliocentric Celestial Reference System
+/// Axes of GCRF, centered on the Sun. The position of the Sun comes from the `sun` module.
+#[derive(Clone, Copy)]
+pub struct HCRS {
+    epoch: Epoch,
+}
 
-    //fn has_obs_time(&self) -> bool {
-    //    true
-    //}
+impl HCRS {
+    pub fn new(epoch: Epoch) -> HCRS {
+        HCRS {
+            epoch
+        }
+    }
+}
 
-    fn to_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        self.teme_to_gcrf_matrix().rotate(point)
+impl Frame for HCRS {
+    fn name(&self) -> String {
+        String::from("HCRS")
     }
 
-    fn from_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        self.gcrf_to_teme_matrix().rotate(point)
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
     }
 }
 
+/// Translation from GCRF to HCRS: position [km] and velocity [km/s] of the Earth seen from the Sun
+fn gcrf_to_hcrs(epoch: &Epoch) -> Transform {
+    let (position, velocity) = sun::state_gcrf(epoch);
 
-pub struct GCRF {
-    date_time: DateTime<Utc>,
+    Transform::translation(position.map(|x| -x), velocity.map(|x| -x))
 }
-impl GCRF {
-    fn new(date_time: DateTime<Utc>) -> GCRF {
-        GCRF {
-            date_time
+
+/// # International Celestial Reference System
+/// Axes of GCRF, centered on the solar system barycenter. The position of the Earth comes from
+/// the JPL ephemeris installed with `spk::set_ephemeris`: the conversions fail without it.
+#[derive(Clone, Copy)]
+pub struct ICRS {
+    epoch: Epoch,
+}
+
+impl ICRS {
+    pub fn new(epoch: Epoch) -> ICRS {
+        ICRS {
+            epoch
         }
     }
 }
 
-impl Frame for GCRF {
+impl Frame for ICRS {
     fn name(&self) -> String {
-        String::from("GCRF")
+        String::from("ICRS")
     }
 
-    fn to_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        point
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
     }
+}
 
-    fn from_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        point
-    }
+/// Translation from GCRF to ICRS: position [km] and velocity [km/s] of the Earth relatively
+/// to the solar system barycenter
+fn gcrf_to_icrs(epoch: &Epoch) -> Result<Transform> {
+    let (position, velocity) = spk::ephemeris_state(spk::EARTH, spk::SOLAR_SYSTEM_BARYCENTER, epoch)?;
+
+    Ok(Transform::translation(position, velocity))
 }
 
-/* Earth-Centered Earth-Fixed */
-// It is only valid for a fixed given time
-pub struct ECEF {
-    date_time: DateTime<Utc>,
+/// # International Terrestrial Reference Frame
+/// Earth-fixed frame: the X axis is at the intersection of the equator and the Greenwich
+/// meridian, the Z axis is the conventional pole.
+///
+/// It is computed with the IAU-76/FK5 reduction, from PEF by applying the polar motion (from
+/// the EOP table). See `ITRS` for the IAU 2006/2000 reduction.
+#[derive(Clone, Copy)]
+pub struct ITRF {
+    epoch: Epoch,
 }
 
-impl ECEF {
-    pub(crate) fn new(date_time: DateTime<Utc>) -> ECEF {
-        ECEF {
-            date_time
+/// Earth-Centered Earth-Fixed
+pub type ECEF = ITRF;
+
+impl ITRF {
+    pub fn new(epoch: Epoch) -> ITRF {
+        ITRF {
+            epoch
         }
     }
 }
 
-impl Frame for ECEF {
+impl Frame for ITRF {
     fn name(&self) -> String {
-        String::from("ECEF") + &self.date_time.to_string()
+        String::from("ITRF")
     }
 
-    fn to_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        //TODO
-        point
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
     }
+}
 
-    fn from_gcrf(&self, point: [f64; 3]) -> [f64; 3]
-    {
-        //TODO
-        [point[0] + self.date_time.time().second() as f64, point[1] + self.date_time.time().second() as f64, point[2] + self.date_time.time().second() as f64]
-    }
+/// Rotation from the Pseudo Earth Fixed frame to ITRF: polar motion
+/// (See Vallado, Fundamentals of Astrodynamics and Applications, IAU-76/FK5 reduction)
+fn pef_to_itrf(epoch: &Epoch) -> Matrix {
+    let eop = eop_at(epoch.julian_day(TimeScale::UTC));
+
+    let (sxp, cxp) = eop.x_p.sin_cos();
+    let (syp, cyp) = eop.y_p.sin_cos();
+
+    // r_PEF = W r_ITRF
+    let w = Matrix::new([[cxp, 0.0, -sxp],
+                         [sxp * syp, cyp, cxp * syp],
+                         [sxp * cyp, -syp, cxp * cyp]]);
+
+    w.transpose()
 }
 
 // ECI: (Earth Center Inertial) -> Not turning with earth
@@ -193,13 +239,11 @@ impl Frame for ECEF {
 //  - ITRF (International Terrestrial Reference Frame)
 //  - PEF  (Pseudo-Earth Fixed)
 //  - TIRS (Terrestrial Intermediate Reference System)
+// Topocentric: (centered on an observer) -> Turning with earth
+//  - SEZ (South, East, Zenith)
+//  - ENU (East, North, Up)
+//  - NED (North, East, Down)
 // Sun-centered
-//  - ICRF (International Celestial Reference Frame)
-//
-// WARNING: Speeds cannot be converted to another frame if the obs_time is not the same.
-//  Either convert the speed with physics (might be really hard to do)
-//  Or Fail when trying to convert a speed at another time. There should be a Speed element.
-//  TODO: Vector should be used for speed vectors. Point should be used for positions
-//   It would be the role of each element to check if they can be safely converted from a frame to
-//   another one
-//   Speeds are ignored for now
+//  - HCRS (Heliocentric Celestial Reference System)
+// Barycentric: (centered on the solar system barycenter)
+//  - ICRS (International Celestial Reference System)
@@ -0,0 +1,127 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* IAU 2006/2000 CIO based reduction
+ *
+ * GCRS <-> CIRS (precession-nutation: CIP X, Y and CIO locator s) <-> TIRS (Earth Rotation
+ * Angle) <-> ITRS (polar motion)
+ *
+ * GCRS is the GCRF frame. See IERS Conventions (2010), chapter 5.
+ */
+
+use crate::eop::eop_at;
+use crate::geometry::Matrix;
+use crate::iau2006;
+use crate::time::{Epoch, TimeScale};
+use crate::traits::Frame;
+
+/// Rotation from GCRS to CIRS: precession-nutation
+pub(super) fn gcrs_to_cirs(epoch: &Epoch) -> Matrix
+{
+    let tt = epoch.julian_day_parts(TimeScale::TT);
+
+    // Without an EOP table, the observed corrections to the CIP are 0
+    let eop = eop_at(epoch.julian_day(TimeScale::UTC));
+
+    let (x, y) = iau2006::cip_xy(tt);
+    let s = iau2006::cio_locator(tt, x, y);
+
+    iau2006::gcrs_to_cirs(x + eop.dx, y + eop.dy, s)
+}
+
+/// Rotation from CIRS to TIRS: Earth Rotation Angle
+pub(super) fn cirs_to_tirs(epoch: &Epoch) -> Matrix
+{
+    let ut1 = epoch.julian_day_parts(TimeScale::UT1);
+
+    iau2006::cirs_to_tirs(iau2006::earth_rotation_angle(ut1))
+}
+
+/// Rotation from TIRS to ITRS: polar motion
+pub(super) fn tirs_to_itrs(epoch: &Epoch) -> Matrix
+{
+    let tt = epoch.julian_day_parts(TimeScale::TT);
+
+    // Without an EOP table, the polar motion is 0
+    let eop = eop_at(epoch.julian_day(TimeScale::UTC));
+
+    iau2006::tirs_to_itrs(eop.x_p, eop.y_p, iau2006::tio_locator(tt))
+}
+
+/// # Celestial Intermediate Reference System
+/// Equator of the Celestial Intermediate Pole, origin at the Celestial Intermediate Origin.
+#[derive(Clone, Copy)]
+pub struct CIRS {
+    epoch: Epoch,
+}
+
+impl CIRS {
+    pub fn new(epoch: Epoch) -> CIRS {
+        CIRS {
+            epoch
+        }
+    }
+}
+
+impl Frame for CIRS {
+    fn name(&self) -> String {
+        String::from("CIRS")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
+
+/// # Terrestrial Intermediate Reference System
+/// Rotates with the Earth around the Celestial Intermediate Pole. It differs from ITRS by the
+/// polar motion only.
+#[derive(Clone, Copy)]
+pub struct TIRS {
+    epoch: Epoch,
+}
+
+impl TIRS {
+    pub fn new(epoch: Epoch) -> TIRS {
+        TIRS {
+            epoch
+        }
+    }
+}
+
+impl Frame for TIRS {
+    fn name(&self) -> String {
+        String::from("TIRS")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
+
+/// # International Terrestrial Reference System
+/// Same axes as `ITRF`, computed with the IAU 2006/2000 models instead of the IAU-76/FK5 ones.
+#[derive(Clone, Copy)]
+pub struct ITRS {
+    epoch: Epoch,
+}
+
+impl ITRS {
+    pub fn new(epoch: Epoch) -> ITRS {
+        ITRS {
+            epoch
+        }
+    }
+}
+
+impl Frame for ITRS {
+    fn name(&self) -> String {
+        String::from("ITRS")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
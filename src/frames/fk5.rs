@@ -0,0 +1,275 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* IAU-76/FK5 reduction
+ *
+ * GCRF <-> MOD (precession) <-> TOD (nutation) <-> PEF (Greenwich Apparent Sidereal Time)
+ *
+ * TEME is between TOD and PEF: it only lacks the equation of the equinoxes.
+ * J2000 differs from GCRF by the frame bias.
+ * See Vallado, Fundamentals of Astrodynamics and Applications, 3.7
+ */
+
+use std::f64::consts::PI;
+use crate::algebra::evalpoly;
+use crate::eop::eop_at;
+use crate::geometry::{Matrix, RotationAxis};
+use crate::kf5;
+use crate::time::{Epoch, TimeScale};
+use crate::traits::Frame;
+
+const MILLIARCSEC_TO_RAD: f64 = PI / 648000000.0;
+
+/// Nutation rotations of the FK5 reduction at a given epoch
+struct Nutation {
+    tod_to_mod: Matrix,
+    teme_to_tod: Matrix,
+}
+
+impl Nutation {
+    fn at(epoch: &Epoch) -> Nutation
+    {
+        // Get the EOP data related to the desired epoch.
+        // Without an EOP table, the corrections are 0 and GCRF is closer to J2000
+        let eop = eop_at(epoch.julian_day(TimeScale::UTC));
+
+        Nutation::corrected(epoch, eop.dpsi, eop.deps)
+    }
+
+    /// Nutation with the given corrections in longitude and obliquity [rad]
+    fn corrected(epoch: &Epoch, dpsi: f64, deps: f64) -> Nutation
+    {
+        // Get the time in TT.
+        let tt = epoch.julian_day(TimeScale::TT);
+
+        // Compute the nutation in the Julian Day (Terrestrial Time) `JD_TT`.
+        let nutation = kf5::nutation(tt);
+
+        // Add the corrections to the nutation in obliquity and longitude.
+        let a = nutation[0];
+        let b = nutation[1] + deps;
+        let c = nutation[2] + dpsi;
+
+        let obliquity = a + b;
+
+        // Evaluate the Delaunay parameters associated with the Moon in the interval
+        // [0,2π]°.
+        //
+        // The parameters here were updated as stated in the errata [2].
+        let t_tt = epoch.julian_centuries(TimeScale::TT);
+        let r = 360.0;
+        let mut delaunay = evalpoly(
+            t_tt,
+            vec![125.04452222,
+                 -5.0 * r - 134.1362608,
+                 0.0020708,
+                 2.2e-6],
+        );
+        delaunay = (delaunay % 360.0) * PI / 180.0;
+
+        // Compute the equation of Equinoxes.
+        //
+        // According to [2], the constant unit before `sin(2Ω_m)` is also in [rad].
+        let eq_equinox1982 = c * a.cos() +
+            (0.002640 * delaunay.sin() + 0.000063 * (2.0 * delaunay).sin()) * PI / 648000.0;
+
+        Nutation {
+            tod_to_mod: Matrix::rot_from_angles(obliquity, c, -a, RotationAxis::XZX),
+            teme_to_tod: Matrix::rot_from_angles(-eq_equinox1982, 0.0, 0.0, RotationAxis::ZYX),
+        }
+    }
+}
+
+/// Rotation from GCRF to J2000: frame bias
+pub(super) fn gcrf_to_j2000() -> Matrix
+{
+    let d_alpha0 = -14.6 * MILLIARCSEC_TO_RAD;
+    let xi0 = -16.6170 * MILLIARCSEC_TO_RAD;
+    let eta0 = -6.8192 * MILLIARCSEC_TO_RAD;
+
+    // B = R1(-eta0) R2(xi0) R3(d_alpha0)
+    Matrix::rot_from_angles(d_alpha0, xi0, -eta0, RotationAxis::ZYX)
+}
+
+/// Rotation from GCRF to MOD: precession
+pub(crate) fn gcrf_to_mod(epoch: &Epoch) -> Matrix
+{
+    let precession = kf5::precession(epoch.julian_day(TimeScale::TT));
+    let mod_to_gcrf = Matrix::rot_from_angles(precession[2], -precession[1], precession[0], RotationAxis::ZYZ);
+
+    // Transpose of a rotation is its inverse
+    mod_to_gcrf.transpose()
+}
+
+/// Rotation from the mean ecliptic and equinox of date to MOD: mean obliquity
+pub(crate) fn ecliptic_to_mod(epoch: &Epoch) -> Matrix
+{
+    let obliquity = kf5::nutation(epoch.julian_day(TimeScale::TT))[0];
+
+    // Transpose of a rotation is its inverse
+    Matrix::rot_from_angles(obliquity, 0.0, 0.0, RotationAxis::XZX).transpose()
+}
+
+/// Coordinates X, Y in GCRF [rad] of the celestial pole of the reduction (the Z axis of TOD),
+/// with the given corrections to the nutation in longitude and obliquity [rad]
+pub(crate) fn celestial_pole(epoch: &Epoch, dpsi: f64, deps: f64) -> (f64, f64)
+{
+    let tod_to_mod = Nutation::corrected(epoch, dpsi, deps).tod_to_mod;
+    let pole = gcrf_to_mod(epoch).transpose().rotate(tod_to_mod.rotate([0.0, 0.0, 1.0]));
+
+    (pole[0], pole[1])
+}
+
+/// Corrections to the nutation in longitude and obliquity [rad] which move the celestial pole
+/// of the reduction to the coordinates X, Y in GCRF [rad].
+///
+/// The pole moves by about sin(ε) dψ along X and dε along Y: the corrections are found by a
+/// Newton iteration, with the derivatives computed by finite differences.
+pub(crate) fn nutation_corrections(epoch: &Epoch, x: f64, y: f64) -> (f64, f64)
+{
+    // 1 mas, the move of the pole is linear well beyond this step
+    let step = MILLIARCSEC_TO_RAD;
+    let mut dpsi = 0.0;
+    let mut deps = 0.0;
+
+    for _ in 0..2 {
+        let (x0, y0) = celestial_pole(epoch, dpsi, deps);
+        let (x_psi, y_psi) = celestial_pole(epoch, dpsi + step, deps);
+        let (x_eps, y_eps) = celestial_pole(epoch, dpsi, deps + step);
+
+        // Solve J (ddpsi, ddeps) = (x - x0, y - y0)
+        let j = [[(x_psi - x0) / step, (x_eps - x0) / step],
+                 [(y_psi - y0) / step, (y_eps - y0) / step]];
+        let det = j[0][0] * j[1][1] - j[0][1] * j[1][0];
+
+        dpsi += (j[1][1] * (x - x0) - j[0][1] * (y - y0)) / det;
+        deps += (j[0][0] * (y - y0) - j[1][0] * (x - x0)) / det;
+    }
+
+    (dpsi, deps)
+}
+
+/// Rotation from MOD to TOD: nutation
+pub(super) fn mod_to_tod(epoch: &Epoch) -> Matrix
+{
+    Nutation::at(epoch).tod_to_mod.transpose()
+}
+
+/// Rotation from TOD to TEME: equation of the equinoxes
+pub(super) fn tod_to_teme(epoch: &Epoch) -> Matrix
+{
+    Nutation::at(epoch).teme_to_tod.transpose()
+}
+
+/// Rotation from TEME to the Pseudo Earth Fixed frame: rotation of the Earth around the
+/// Celestial Ephemeris Pole by the Greenwich Mean Sidereal Time
+pub(super) fn teme_to_pef(epoch: &Epoch) -> Matrix
+{
+    Matrix::rot_from_angles(epoch.gmst(), 0.0, 0.0, RotationAxis::ZYX)
+}
+
+/// # Mean Of Date
+/// Mean equator and mean equinox of the given date: only the precession is applied.
+#[derive(Clone, Copy)]
+pub struct MOD {
+    epoch: Epoch,
+}
+
+impl MOD {
+    pub fn new(epoch: Epoch) -> MOD {
+        MOD {
+            epoch
+        }
+    }
+}
+
+impl Frame for MOD {
+    fn name(&self) -> String {
+        String::from("MOD")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
+
+/// # True Of Date
+/// True equator and true equinox of the given date: precession and nutation are applied.
+#[derive(Clone, Copy)]
+pub struct TOD {
+    epoch: Epoch,
+}
+
+impl TOD {
+    pub fn new(epoch: Epoch) -> TOD {
+        TOD {
+            epoch
+        }
+    }
+}
+
+impl Frame for TOD {
+    fn name(&self) -> String {
+        String::from("TOD")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
+
+/// # Pseudo Earth Fixed
+/// Rotates with the Earth around the Celestial Ephemeris Pole. It differs from ITRF by the
+/// polar motion only.
+#[derive(Clone, Copy)]
+pub struct PEF {
+    epoch: Epoch,
+}
+
+impl PEF {
+    pub fn new(epoch: Epoch) -> PEF {
+        PEF {
+            epoch
+        }
+    }
+}
+
+impl Frame for PEF {
+    fn name(&self) -> String {
+        String::from("PEF")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+}
+
+/// # J2000 (EME2000)
+/// Mean equator and mean equinox at 2000-01-01 12:00 TT. It differs from GCRF by a
+/// constant frame bias of a few tens of milliarcseconds (IERS Conventions 2003, 5.4.4).
+#[derive(Clone, Copy)]
+pub struct J2000;
+
+impl J2000 {
+    pub fn new() -> J2000 {
+        J2000
+    }
+}
+
+impl Default for J2000 {
+    fn default() -> Self {
+        J2000::new()
+    }
+}
+
+impl Frame for J2000 {
+    fn name(&self) -> String {
+        String::from("J2000")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        None
+    }
+}
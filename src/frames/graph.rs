@@ -0,0 +1,330 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Frame transformation graph
+ *
+ * Each frame only declares the transforms to its direct neighbours. A conversion between
+ * two frames composes the transforms along the shortest path between them
+ * (See https://github.com/astropy/astropy/blob/77208dd7d7265df382849de841c890b3af996323/astropy/coordinates/transformations.py#L76)
+ *
+ * Most frames depend on time. When the source and destination frames have different epochs,
+ * the path has to go through an epoch-independent frame (GCRF, J2000, ICRS): the transforms before
+ * it use the epoch of the source frame, the transforms after it the epoch of the destination
+ * frame.
+ *
+ * Velocities are converted with the transport theorem: the time derivatives of the rotation
+ * (e.g. the Earth rotation between TEME and PEF) and of the translation (e.g. the motion of
+ * the Earth around the Sun) are part of the transforms. A velocity is only meaningful at the
+ * epoch it was computed for, so it cannot be converted to a frame at another epoch.
+ *
+ * Frames which depend on other parameters than the epoch (e.g. the topocentric frames of an
+ * observer) are not nodes of the graph: they give their transform from a frame of the graph
+ * with `Frame::transform_from_parent`.
+ */
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::RwLock;
+use crate::geometry::Matrix;
+use crate::time::Epoch;
+use crate::traits::Frame;
+use crate::{Error, Result};
+
+/// Graph used by `Framable::change_frame`. It is built with the tardis frames on first use.
+static TRANSFORM_GRAPH: RwLock<Option<TransformGraph>> = RwLock::new(None);
+
+/// # Affine transform between two frames
+/// `p_to = rotation * p_from + translation`. The translation [km] only applies to positions.
+///
+/// The velocities are transformed with the time derivatives of the rotation and the translation:
+/// `v_to = rotation * v_from + rotation_rate * p_from + velocity`
+#[derive(Clone, Copy)]
+pub struct Transform {
+    rotation: Matrix,
+    rotation_rate: Matrix,
+    translation: [f64; 3],
+    velocity: [f64; 3],
+}
+
+impl Transform {
+    /// Fixed rotation
+    pub fn rotation(rotation: Matrix) -> Transform
+    {
+        Transform::rotating(rotation, [0.0, 0.0, 0.0])
+    }
+
+    /// Rotation to a frame rotating with the angular velocity `omega` [rad/s], expressed in the
+    /// destination frame
+    pub fn rotating(rotation: Matrix, omega: [f64; 3]) -> Transform
+    {
+        Transform {
+            rotation,
+            rotation_rate: Matrix::compose(Matrix::cross_product([-omega[0], -omega[1], -omega[2]]), rotation),
+            translation: [0.0, 0.0, 0.0],
+            velocity: [0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Translation [km] of the origin, moving at the given velocity [km/s]
+    pub fn translation(translation: [f64; 3], velocity: [f64; 3]) -> Transform
+    {
+        Transform {
+            rotation: Matrix::identity(),
+            rotation_rate: Matrix::new([[0.0; 3]; 3]),
+            translation,
+            velocity,
+        }
+    }
+
+    pub fn apply_to_position(&self, point: [f64; 3]) -> [f64; 3]
+    {
+        add(self.rotation.rotate(point), self.translation)
+    }
+
+    pub fn apply_to_direction(&self, vector: [f64; 3]) -> [f64; 3]
+    {
+        self.rotation.rotate(vector)
+    }
+
+    /// Transform a position [km] and a velocity [km/s]
+    pub fn apply_to_state(&self, position: [f64; 3], velocity: [f64; 3]) -> ([f64; 3], [f64; 3])
+    {
+        let v = add(self.rotation.rotate(velocity), self.rotation_rate.rotate(position));
+
+        (self.apply_to_position(position), add(v, self.velocity))
+    }
+
+    /// Inverse transform. The rotation is assumed orthonormal.
+    pub fn inverse(&self) -> Transform
+    {
+        let rotation = self.rotation.transpose();
+        let rotation_rate = self.rotation_rate.transpose();
+        let t = rotation.rotate(self.translation);
+        let v = add(rotation_rate.rotate(self.translation), rotation.rotate(self.velocity));
+
+        Transform {
+            rotation,
+            rotation_rate,
+            translation: [-t[0], -t[1], -t[2]],
+            velocity: [-v[0], -v[1], -v[2]],
+        }
+    }
+
+    /// Transform equivalent to applying `self` then `next`
+    pub fn then(&self, next: &Transform) -> Transform
+    {
+        let (translation, velocity) = next.apply_to_state(self.translation, self.velocity);
+
+        Transform {
+            rotation: Matrix::compose(next.rotation, self.rotation),
+            rotation_rate: Matrix::sum(Matrix::compose(next.rotation_rate, self.rotation),
+                                       Matrix::compose(next.rotation, self.rotation_rate)),
+            translation,
+            velocity,
+        }
+    }
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3]
+{
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// # Function computing the transform along an edge of the graph
+#[derive(Clone, Copy)]
+pub enum TransformFn {
+    /// The transform does not depend on time
+    Fixed(fn() -> Transform),
+    /// The transform depends on the epoch of the frames
+    Timed(fn(&Epoch) -> Transform),
+    /// The transform depends on the epoch of the frames and may be unavailable (e.g. without
+    /// ephemeris)
+    Fallible(fn(&Epoch) -> Result<Transform>),
+}
+
+#[derive(Clone, Copy)]
+struct Edge {
+    function: TransformFn,
+    inverse: bool,
+}
+
+impl Edge {
+    fn transform(&self, epoch: Option<&Epoch>, from: &str, to: &str) -> Result<Transform>
+    {
+        let t = match (self.function, epoch) {
+            (TransformFn::Fixed(f), _) => f(),
+            (TransformFn::Timed(f), Some(e)) => f(e),
+            (TransformFn::Fallible(f), Some(e)) => f(e)?,
+            (TransformFn::Timed(_), None) | (TransformFn::Fallible(_), None) => return Err(Error::Frame(
+                format!("The transform from {} to {} needs an epoch", from, to))),
+        };
+
+        if self.inverse {
+            Ok(t.inverse())
+        } else {
+            Ok(t)
+        }
+    }
+}
+
+/// # Registry of the transforms between frames, identified by their name
+#[derive(Clone, Default)]
+pub struct TransformGraph {
+    edges: HashMap<String, HashMap<String, Edge>>,
+    epoch_independent: HashSet<String>,
+}
+
+impl TransformGraph {
+    /// Empty graph
+    pub fn new() -> TransformGraph
+    {
+        TransformGraph::default()
+    }
+
+    /// Declare the direct transform from a frame to another one. The opposite transform is
+    /// the inverse.
+    pub fn add_transform(&mut self, from: &str, to: &str, function: TransformFn)
+    {
+        self.edges.entry(String::from(from)).or_default()
+            .insert(String::from(to), Edge { function, inverse: false });
+        self.edges.entry(String::from(to)).or_default()
+            .insert(String::from(from), Edge { function, inverse: true });
+    }
+
+    /// Declare that the orientation and the origin of a frame do not depend on time
+    pub fn add_epoch_independent(&mut self, frame: &str)
+    {
+        self.epoch_independent.insert(String::from(frame));
+    }
+
+    /// Shortest path between two frames. If `through_epoch_independent` is set, the path goes
+    /// through an epoch-independent frame.
+    pub fn path(&self, from: &str, to: &str, through_epoch_independent: bool) -> Option<Vec<String>>
+    {
+        // Breadth first search on (frame, already went through an epoch-independent frame)
+        let start = (String::from(from), self.epoch_independent.contains(from));
+        let mut previous: HashMap<(String, bool), (String, bool)> = HashMap::new();
+        let mut visited = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            if node.0 == to && (node.1 || !through_epoch_independent) {
+                let mut path = vec![node.0.clone()];
+                let mut current = node;
+                while let Some(p) = previous.get(&current) {
+                    path.push(p.0.clone());
+                    current = p.clone();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            if let Some(neighbours) = self.edges.get(&node.0) {
+                for neighbour in neighbours.keys() {
+                    let next = (neighbour.clone(), node.1 || self.epoch_independent.contains(neighbour));
+                    if visited.insert(next.clone()) {
+                        previous.insert(next.clone(), node.clone());
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Compose the transforms from a frame to another one
+    pub fn transform(&self, from: &dyn Frame, to: &dyn Frame) -> Result<Transform>
+    {
+        // The frames outside of the graph are attached to a frame of the graph
+        let (from_name, before) = match from.transform_from_parent() {
+            Some((parent, t)) => (parent, t.inverse()),
+            None => (from.name(), Transform::rotation(Matrix::identity())),
+        };
+        let (to_name, after) = match to.transform_from_parent() {
+            Some((parent, t)) => (parent, t),
+            None => (to.name(), Transform::rotation(Matrix::identity())),
+        };
+
+        let transform = self.transform_by_name(&from_name, from.epoch(), &to_name, to.epoch())?;
+
+        Ok(before.then(&transform).then(&after))
+    }
+
+    fn transform_by_name(&self, from_name: &str, from_epoch: Option<Epoch>, to_name: &str, to_epoch: Option<Epoch>)
+        -> Result<Transform>
+    {
+        let different_epochs = matches!((from_epoch, to_epoch), (Some(a), Some(b)) if a != b);
+
+        let path = match self.path(from_name, to_name, different_epochs) {
+            Some(p) => p,
+            None => return Err(Error::Frame(format!("No transform from {} to {}", from_name, to_name))),
+        };
+
+        let mut transform = Transform::rotation(Matrix::identity());
+        let mut epoch = if self.epoch_independent.contains(from_name) { to_epoch } else { from_epoch };
+
+        for step in path.windows(2) {
+            let edge = self.edges[&step[0]][&step[1]];
+            transform = transform.then(&edge.transform(epoch.as_ref(), &step[0], &step[1])?);
+
+            if self.epoch_independent.contains(&step[1]) {
+                epoch = to_epoch;
+            }
+        }
+
+        Ok(transform)
+    }
+}
+
+/// Run `f` with the graph used by `Framable::change_frame`
+fn with_graph<R>(f: impl FnOnce(&TransformGraph) -> R) -> R
+{
+    {
+        let graph = TRANSFORM_GRAPH.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(g) = &*graph {
+            return f(g);
+        }
+    }
+
+    let mut graph = TRANSFORM_GRAPH.write().unwrap_or_else(|e| e.into_inner());
+    f(graph.get_or_insert_with(super::builtin_graph))
+}
+
+/// Declare a direct transform in the graph used by `Framable::change_frame`, e.g. to add a
+/// new frame next to an existing one.
+pub fn register_transform(from: &str, to: &str, function: TransformFn)
+{
+    let mut graph = TRANSFORM_GRAPH.write().unwrap_or_else(|e| e.into_inner());
+    graph.get_or_insert_with(super::builtin_graph).add_transform(from, to, function);
+}
+
+/// Convert a position from a frame to another one
+pub fn convert_position(from: &dyn Frame, to: &dyn Frame, point: [f64; 3]) -> Result<[f64; 3]>
+{
+    with_graph(|g| g.transform(from, to)).map(|t| t.apply_to_position(point))
+}
+
+/// Convert a direction from a frame to another one: only the rotation is applied
+pub fn convert_direction(from: &dyn Frame, to: &dyn Frame, vector: [f64; 3]) -> Result<[f64; 3]>
+{
+    with_graph(|g| g.transform(from, to)).map(|t| t.apply_to_direction(vector))
+}
+
+/// Convert a position [km] and a velocity [km/s] from a frame to another one.
+///
+/// Fails if the frames have different epochs: the velocity is only valid at the epoch of the
+/// source frame.
+pub fn convert_state(from: &dyn Frame, to: &dyn Frame, position: [f64; 3], velocity: [f64; 3])
+    -> Result<([f64; 3], [f64; 3])>
+{
+    if let (Some(a), Some(b)) = (from.epoch(), to.epoch()) {
+        if a != b {
+            return Err(Error::Frame(format!("Cannot convert a velocity from {} at {} to {} at {}",
+                                            from.name(), a, to.name(), b)));
+        }
+    }
+
+    with_graph(|g| g.transform(from, to)).map(|t| t.apply_to_state(position, velocity))
+}
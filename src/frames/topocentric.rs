@@ -0,0 +1,136 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Topocentric frames
+ *
+ * Local horizon frames centered on an observer on the Earth, attached to ITRF:
+ *  - SEZ: South, East, Zenith (See Vallado, Fundamentals of Astrodynamics and Applications, 3.4)
+ *  - ENU: East, North, Up
+ *  - NED: North, East, Down
+ */
+
+use crate::geometry::Matrix;
+use crate::time::Epoch;
+use crate::traits::Frame;
+use crate::utils::Observer;
+use super::Transform;
+
+/// Unit vectors of the local horizon of the observer in ITRF: (south, east, zenith)
+fn horizon_axes(observer: &Observer) -> ([f64; 3], [f64; 3], [f64; 3])
+{
+    let (slat, clat) = observer.coordinates().latitude().to_radians().sin_cos();
+    let (slon, clon) = observer.coordinates().longitude().to_radians().sin_cos();
+
+    let south = [slat * clon, slat * slon, -clat];
+    let east = [-slon, clon, 0.0];
+    let zenith = [clat * clon, clat * slon, slat];
+
+    (south, east, zenith)
+}
+
+/// Transform from ITRF to the frame centered on the observer, with the given axes (in ITRF)
+fn from_itrf(observer: &Observer, axes: [[f64; 3]; 3]) -> (String, Transform)
+{
+    let site = observer.coordinates().to_cartesian();
+    let origin = Transform::translation(site.map(|x| -x), [0.0, 0.0, 0.0]);
+
+    (String::from("ITRF"), origin.then(&Transform::rotation(Matrix::new(axes))))
+}
+
+/// # South, East, Zenith frame of an observer
+#[derive(Clone, Copy)]
+pub struct SEZ {
+    observer: Observer,
+    epoch: Epoch,
+}
+
+impl SEZ {
+    pub fn new(observer: &Observer, epoch: Epoch) -> SEZ {
+        SEZ {
+            observer: *observer,
+            epoch
+        }
+    }
+}
+
+impl Frame for SEZ {
+    fn name(&self) -> String {
+        String::from("SEZ")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+
+    fn transform_from_parent(&self) -> Option<(String, Transform)> {
+        let (south, east, zenith) = horizon_axes(&self.observer);
+        Some(from_itrf(&self.observer, [south, east, zenith]))
+    }
+}
+
+/// # East, North, Up frame of an observer
+#[derive(Clone, Copy)]
+pub struct ENU {
+    observer: Observer,
+    epoch: Epoch,
+}
+
+impl ENU {
+    pub fn new(observer: &Observer, epoch: Epoch) -> ENU {
+        ENU {
+            observer: *observer,
+            epoch
+        }
+    }
+}
+
+impl Frame for ENU {
+    fn name(&self) -> String {
+        String::from("ENU")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+
+    fn transform_from_parent(&self) -> Option<(String, Transform)> {
+        let (south, east, zenith) = horizon_axes(&self.observer);
+        let north = south.map(|x| -x);
+        Some(from_itrf(&self.observer, [east, north, zenith]))
+    }
+}
+
+/// # North, East, Down frame of an observer
+#[derive(Clone, Copy)]
+pub struct NED {
+    observer: Observer,
+    epoch: Epoch,
+}
+
+impl NED {
+    pub fn new(observer: &Observer, epoch: Epoch) -> NED {
+        NED {
+            observer: *observer,
+            epoch
+        }
+    }
+}
+
+impl Frame for NED {
+    fn name(&self) -> String {
+        String::from("NED")
+    }
+
+    fn epoch(&self) -> Option<Epoch> {
+        Some(self.epoch)
+    }
+
+    fn transform_from_parent(&self) -> Option<(String, Transform)> {
+        let (south, east, zenith) = horizon_axes(&self.observer);
+        let north = south.map(|x| -x);
+        let down = zenith.map(|x| -x);
+        Some(from_itrf(&self.observer, [north, east, down]))
+    }
+}
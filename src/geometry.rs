@@ -18,8 +18,13 @@ use std::rc::Rc;
 use chrono::{DateTime, Timelike, Utc};
 use sgp4::sgp4::SGP4;
 use crate::traits::{Frame, Framable};
+use crate::frames::{convert_direction, convert_position, convert_state};
 use crate::kf5::{nutation, precession};
-use crate::time::{get_leap_seconds, jd_utc_to_tt};
+use crate::time::Epoch;
+use crate::{Error, Result};
+
+mod typed;
+pub use typed::*;
 
 pub enum RotationAxis {
     ZYZ,
@@ -27,6 +32,7 @@ pub enum RotationAxis {
     XZX,
 }
 
+#[derive(Clone, Copy)]
 pub struct Matrix {
     values: [[f64; 3]; 3],
 }
@@ -44,9 +50,15 @@ impl Matrix {
         }
     }
 
+    pub fn identity() -> Matrix {
+        Matrix {
+            values: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        }
+    }
+
     pub fn determinant(&self) -> f64 {
-        self.values[0][0] * self.values[1][1] * self.values[2][2] - self.values[0][0] * self.values[1][2] * self.values[2][1] +
-            self.values[0][1] * self.values[1][0] * self.values[2][2] - self.values[0][1] * self.values[1][2] * self.values[2][0] +
+        self.values[0][0] * self.values[1][1] * self.values[2][2] - self.values[0][0] * self.values[1][2] * self.values[2][1] -
+            self.values[0][1] * self.values[1][0] * self.values[2][2] + self.values[0][1] * self.values[1][2] * self.values[2][0] +
             self.values[0][2] * self.values[1][0] * self.values[2][1] - self.values[0][2] * self.values[1][1] * self.values[2][0]
     }
 
@@ -58,11 +70,11 @@ impl Matrix {
         }
     }
 
-    pub fn invert(&self) -> Result<Matrix, String> {
+    pub fn invert(&self) -> Result<Matrix> {
         let det = self.determinant();
 
         if det.abs() - 0.0 < 1e-10 {
-            return Err(String::from("Matrix cannot be inverted"));
+            return Err(Error::Math(String::from("Matrix cannot be inverted")));
         }
 
         let t = self.transpose();
@@ -97,6 +109,26 @@ impl Matrix {
         ret
     }
 
+    pub fn sum(a: Matrix, b: Matrix) -> Matrix {
+        let mut ret = a;
+
+        for i in 0..3 {
+            for j in 0..3 {
+                ret.values[i][j] += b.values[i][j];
+            }
+        }
+
+        ret
+    }
+
+    ///
+    /// Matrix of the cross product by `v`: `cross_product(v).rotate(p)` is `v × p`
+    pub fn cross_product(v: [f64; 3]) -> Matrix {
+        Matrix::new([[0.0, -v[2], v[1]],
+                     [v[2], 0.0, -v[0]],
+                     [-v[1], v[0], 0.0]])
+    }
+
     ///
     /// Compute a rotation matrix from the 3 angles, with the given Rotation axis
     /// TODO: Understand the rotation axis argument
@@ -179,13 +211,14 @@ impl Point {
 }
 
 impl Framable for Point {
-    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) {
+    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) -> Result<()> {
         self.coordinates = match self.frame {
-            Some(ref f) => new_frame.from_gcrf(f.to_gcrf(self.coordinates)),
+            Some(ref f) => convert_position(f.as_ref(), new_frame.as_ref(), self.coordinates)?,
             None => self.coordinates,
         };
 
         self.frame = Some(new_frame);
+        Ok(())
     }
 
     fn set_frame(&mut self, frame: Rc<dyn Frame>) {
@@ -218,10 +251,10 @@ impl fmt::Display for Point {
 }
 
 impl Plane {
-    pub fn from_vectors(a: &Vector, b: &Vector, c: &Vector) -> Result<Plane, String>
+    pub fn from_vectors(a: &Vector, b: &Vector, c: &Vector) -> Result<Plane>
     {
         if a == b || b == c || c == a {
-            return Err(String::from("The vectors must all be different"));
+            return Err(Error::Math(String::from("The vectors must all be different")));
         }
 
         Ok(Plane {
@@ -231,7 +264,7 @@ impl Plane {
         })
     }
 
-    pub fn from_vectors_normal(normal: &Vector, b: &Vector, c: &Vector) -> Result<Plane, String>
+    pub fn from_vectors_normal(normal: &Vector, b: &Vector, c: &Vector) -> Result<Plane>
     {
         return match Plane::from_vectors(normal, b, c) {
             Ok(mut p) => {
@@ -438,13 +471,16 @@ impl PartialEq for Vector {
 impl Eq for Vector {}
 
 impl Framable for Vector {
-    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) {
+    /// Only the rotation between the frames is applied: a velocity is converted with
+    /// `frames::convert_state`, which also needs the position.
+    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) -> Result<()> {
         self.vector = match self.frame {
-            Some(ref f) => new_frame.from_gcrf(f.to_gcrf(self.vector)),
+            Some(ref f) => convert_direction(f.as_ref(), new_frame.as_ref(), self.vector)?,
             None => self.vector,
         };
 
         self.frame = Some(new_frame);
+        Ok(())
     }
 
     fn set_frame(&mut self, frame: Rc<dyn Frame>) {
@@ -452,6 +488,96 @@ impl Framable for Vector {
     }
 }
 
+/// # Position [km] and velocity [km/s] of an object at a given epoch, in a given frame
+#[derive(Clone)]
+pub struct StateVector {
+    position: [f64; 3],
+    velocity: [f64; 3],
+    epoch: Epoch,
+    frame: Rc<dyn Frame>,
+}
+
+impl StateVector {
+    /// Create a state vector. The frame must be at the epoch of the state (or
+    /// epoch-independent).
+    pub fn new(position: [f64; 3], velocity: [f64; 3], epoch: Epoch, frame: Rc<dyn Frame>) -> Result<StateVector>
+    {
+        if let Some(e) = frame.epoch() {
+            if e != epoch {
+                return Err(Error::Frame(format!("Cannot create a state vector at {} in {} at {}",
+                                                epoch, frame.name(), e)));
+            }
+        }
+
+        Ok(StateVector {
+            position,
+            velocity,
+            epoch,
+            frame,
+        })
+    }
+
+    pub fn position(&self) -> Point
+    {
+        Point {
+            coordinates: self.position,
+            frame: Some(Rc::clone(&self.frame)),
+        }
+    }
+
+    pub fn velocity(&self) -> Vector
+    {
+        Vector {
+            vector: self.velocity,
+            frame: Some(Rc::clone(&self.frame)),
+        }
+    }
+
+    pub fn epoch(&self) -> Epoch
+    {
+        self.epoch
+    }
+
+    pub fn frame(&self) -> Rc<dyn Frame>
+    {
+        Rc::clone(&self.frame)
+    }
+}
+
+impl Framable for StateVector {
+    /// Convert the position and the velocity together. The new frame must be at the epoch of
+    /// the state vector (or epoch-independent).
+    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) -> Result<()> {
+        if let Some(e) = new_frame.epoch() {
+            if e != self.epoch {
+                return Err(Error::Frame(format!("Cannot convert a state vector at {} to {} at {}",
+                                                self.epoch, new_frame.name(), e)));
+            }
+        }
+
+        let (position, velocity) = convert_state(self.frame.as_ref(), new_frame.as_ref(), self.position, self.velocity)?;
+
+        self.position = position;
+        self.velocity = velocity;
+        self.frame = new_frame;
+        Ok(())
+    }
+
+    fn set_frame(&mut self, frame: Rc<dyn Frame>) {
+        self.frame = frame;
+    }
+}
+
+impl fmt::Display for StateVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StateVector ({}) at {}: position [{}, {}, {}] velocity [{}, {}, {}]",
+               self.frame.name(), self.epoch,
+               self.position[0], self.position[1], self.position[2],
+               self.velocity[0], self.velocity[1], self.velocity[2])
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct Angle {
     degrees: f64,
     radians: f64,
@@ -482,16 +608,18 @@ impl Angle {
             return Angle::from_degrees(0f64);
         }
 
-        let cos = (a * b) / den;
+        // Rounding can push the cosine of nearly parallel vectors out of [-1, 1]
+        let cos = ((a * b) / den).clamp(-1.0, 1.0);
 
         Angle::from_radians(cos.acos())
     }
 
+    /// Same angle in [0°, 360°)
     pub fn canonical(&self) -> Angle
     {
         Angle {
-            degrees: self.degrees % 360.0,
-            radians: self.radians % (2.0 * PI),
+            degrees: self.degrees.rem_euclid(360.0),
+            radians: self.radians.rem_euclid(2.0 * PI),
         }
     }
 
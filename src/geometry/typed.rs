@@ -0,0 +1,215 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Points and vectors with the frame as a type parameter
+ *
+ * Combining elements of different frames does not compile, and the conversions are directed
+ * by the type of the destination frame:
+ *
+ *   let p = FramedPoint::new(1.1, 1.2, 1.3, TEME::new(epoch));
+ *   let p2: FramedPoint<ITRF> = p.to_frame(ITRF::new(epoch))?;
+ *
+ * The frame value is kept to know its epoch. Elements of the same frame type at different
+ * epochs are still different frames: the operators panic when combining them, the
+ * elements have to be converted to a common epoch with `to_frame` first.
+ */
+
+use std::fmt;
+use std::ops::{Add, Index, Mul, Sub};
+use std::rc::Rc;
+use crate::frames::{convert_direction, convert_position};
+use crate::traits::{Framable, Frame};
+use crate::Result;
+use super::{Point, Vector};
+
+/// # Position [km] in the frame `F`
+#[derive(Clone, Copy)]
+pub struct FramedPoint<F: Frame> {
+    coordinates: [f64; 3],
+    frame: F,
+}
+
+impl<F: Frame + Clone> FramedPoint<F> {
+    pub fn new(x: f64, y: f64, z: f64, frame: F) -> FramedPoint<F>
+    {
+        FramedPoint::from_tuple([x, y, z], frame)
+    }
+
+    pub fn from_tuple(coordinates: [f64; 3], frame: F) -> FramedPoint<F>
+    {
+        FramedPoint {
+            coordinates,
+            frame,
+        }
+    }
+
+    pub fn coordinates(&self) -> [f64; 3]
+    {
+        self.coordinates
+    }
+
+    pub fn frame(&self) -> &F
+    {
+        &self.frame
+    }
+
+    /// Convert the point to another frame
+    pub fn to_frame<G: Frame>(&self, frame: G) -> Result<FramedPoint<G>>
+    {
+        Ok(FramedPoint {
+            coordinates: convert_position(&self.frame, &frame, self.coordinates)?,
+            frame,
+        })
+    }
+}
+
+/// # Direction or displacement in the frame `F`
+/// Only the rotation between the frames is applied by the conversions.
+#[derive(Clone, Copy)]
+pub struct FramedVector<F: Frame> {
+    vector: [f64; 3],
+    frame: F,
+}
+
+impl<F: Frame + Clone> FramedVector<F> {
+    pub fn new(x: f64, y: f64, z: f64, frame: F) -> FramedVector<F>
+    {
+        FramedVector::from_tuple([x, y, z], frame)
+    }
+
+    pub fn from_tuple(vector: [f64; 3], frame: F) -> FramedVector<F>
+    {
+        FramedVector {
+            vector,
+            frame,
+        }
+    }
+
+    pub fn vector(&self) -> [f64; 3]
+    {
+        self.vector
+    }
+
+    pub fn frame(&self) -> &F
+    {
+        &self.frame
+    }
+
+    pub fn length(&self) -> f64
+    {
+        (self.vector[0].powi(2) + self.vector[1].powi(2) + self.vector[2].powi(2)).sqrt()
+    }
+
+    /// Convert the vector to another frame
+    pub fn to_frame<G: Frame>(&self, frame: G) -> Result<FramedVector<G>>
+    {
+        Ok(FramedVector {
+            vector: convert_direction(&self.frame, &frame, self.vector)?,
+            frame,
+        })
+    }
+}
+
+/// Both operands must be at the same epoch: the frame of the left operand is kept
+///
+/// # Panics
+/// If the epochs of the frames differ
+fn check_epochs<F: Frame>(a: &F, b: &F)
+{
+    assert!(a.epoch() == b.epoch(), "Combining {} elements at different epochs", a.name());
+}
+
+impl<F: Frame + Clone> Add<FramedVector<F>> for FramedPoint<F> {
+    type Output = FramedPoint<F>;
+
+    fn add(self, rhs: FramedVector<F>) -> Self::Output {
+        check_epochs(&self.frame, &rhs.frame);
+        FramedPoint::from_tuple([0, 1, 2].map(|i| self.coordinates[i] + rhs.vector[i]), self.frame)
+    }
+}
+
+impl<F: Frame + Clone> Sub for FramedPoint<F> {
+    type Output = FramedVector<F>;
+
+    /// Vector from `rhs` to `self`
+    fn sub(self, rhs: Self) -> Self::Output {
+        check_epochs(&self.frame, &rhs.frame);
+        FramedVector::from_tuple([0, 1, 2].map(|i| self.coordinates[i] - rhs.coordinates[i]), self.frame)
+    }
+}
+
+impl<F: Frame + Clone> Add for FramedVector<F> {
+    type Output = FramedVector<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        check_epochs(&self.frame, &rhs.frame);
+        FramedVector::from_tuple([0, 1, 2].map(|i| self.vector[i] + rhs.vector[i]), self.frame)
+    }
+}
+
+impl<F: Frame + Clone> Sub for FramedVector<F> {
+    type Output = FramedVector<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        check_epochs(&self.frame, &rhs.frame);
+        FramedVector::from_tuple([0, 1, 2].map(|i| self.vector[i] - rhs.vector[i]), self.frame)
+    }
+}
+
+impl<F: Frame + Clone> Mul for FramedVector<F> {
+    type Output = f64;
+
+    /// Return the scalar product of the 2 Vectors
+    fn mul(self, rhs: Self) -> Self::Output {
+        check_epochs(&self.frame, &rhs.frame);
+        self.vector[0] * rhs.vector[0] + self.vector[1] * rhs.vector[1] + self.vector[2] * rhs.vector[2]
+    }
+}
+
+impl<F: Frame> Index<usize> for FramedPoint<F> {
+    type Output = f64;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.coordinates[idx]
+    }
+}
+
+impl<F: Frame> Index<usize> for FramedVector<F> {
+    type Output = f64;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.vector[idx]
+    }
+}
+
+/// Untyped point, with the frame known at runtime
+impl<F: Frame + 'static> From<FramedPoint<F>> for Point {
+    fn from(p: FramedPoint<F>) -> Point {
+        let mut point = Point::from_tuple(p.coordinates);
+        point.set_frame(Rc::new(p.frame));
+        point
+    }
+}
+
+/// Untyped vector, with the frame known at runtime
+impl<F: Frame + 'static> From<FramedVector<F>> for Vector {
+    fn from(v: FramedVector<F>) -> Vector {
+        let mut vector = Vector::from_tuple(v.vector);
+        vector.set_frame(Rc::new(v.frame));
+        vector
+    }
+}
+
+impl<F: Frame> fmt::Display for FramedPoint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Point ({}) [{}, {}, {}]", self.frame.name(), self.coordinates[0], self.coordinates[1], self.coordinates[2])
+    }
+}
+
+impl<F: Frame> fmt::Display for FramedVector<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Vector ({}) [{}, {}, {}]", self.frame.name(), self.vector[0], self.vector[1], self.vector[2])
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* IAU 2006/2000A nutation
+ *
+ * The 1365 luni-solar and planetary terms are read from the tables of the IERS Conventions
+ * (2010), chapter 5:
+ *  - tab5.3a.txt, nutation in longitude (https://iers-conventions.obspm.fr/content/chapter5/additional_info/tab5.3a.txt)
+ *  - tab5.3b.txt, nutation in obliquity (https://iers-conventions.obspm.fr/content/chapter5/additional_info/tab5.3b.txt)
+ *
+ *   Δψ = Σj t^j Σi (A_i sin(ARG) + A"_i cos(ARG))
+ *   Δε = Σj t^j Σi (B_i cos(ARG) + B"_i sin(ARG))
+ *
+ * with the coefficients in µas and ARG = Σk N_k F_k over the 14 fundamental arguments. The
+ * tables already include the adjustments to the IAU 2006 precession.
+ */
+
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use crate::{Error, Result};
+use super::{fundamental_arguments, ARCSEC_TO_RAD};
+
+/// Series installed with `set_nutation_series`
+static NUTATION_SERIES: RwLock<Option<NutationSeries>> = RwLock::new(None);
+
+/// Term of a series: coefficients [µas] of the sine and the cosine of the argument
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    power: i32,
+    multipliers: [f64; 14],
+    sin: f64,
+    cos: f64,
+}
+
+/// # Series of the IAU 2006/2000A nutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutationSeries {
+    longitude: Vec<Term>,
+    obliquity: Vec<Term>,
+}
+
+impl NutationSeries {
+    /// Load the IERS tables 5.3a (longitude) and 5.3b (obliquity)
+    pub fn from_files<P: AsRef<Path>>(longitude: P, obliquity: P) -> Result<NutationSeries>
+    {
+        let read = |path: P| match fs::read_to_string(path) {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::Io(e)),
+        };
+
+        NutationSeries::parse(&read(longitude)?, &read(obliquity)?)
+    }
+
+    /// Parse the content of the IERS tables 5.3a (longitude) and 5.3b (obliquity)
+    pub fn parse(longitude: &str, obliquity: &str) -> Result<NutationSeries>
+    {
+        Ok(NutationSeries {
+            longitude: parse_table(longitude)?,
+            obliquity: parse_table(obliquity)?,
+        })
+    }
+
+    /// Nutation in longitude and obliquity [rad] at `t` Julian centuries (TT) since J2000
+    pub(super) fn evaluate(&self, t: f64) -> (f64, f64)
+    {
+        let arguments = arguments(t);
+
+        let series = |terms: &[Term]| -> f64 {
+            // Smallest terms first
+            terms.iter().rev().map(|term| {
+                let arg: f64 = term.multipliers.iter().zip(arguments.iter()).map(|(n, a)| n * a).sum();
+                let (sin_arg, cos_arg) = arg.sin_cos();
+
+                (term.sin * sin_arg + term.cos * cos_arg) * t.powi(term.power)
+            }).sum()
+        };
+
+        let to_rad = ARCSEC_TO_RAD * 1e-6;
+
+        (series(&self.longitude) * to_rad, series(&self.obliquity) * to_rad)
+    }
+}
+
+/// Use the given series for the nutation of the CIO based frames
+pub fn set_nutation_series(series: NutationSeries)
+{
+    let mut installed = NUTATION_SERIES.write().unwrap_or_else(|e| e.into_inner());
+    *installed = Some(series);
+}
+
+/// Remove the series installed with `set_nutation_series`: the IAU 2000B model is used
+pub fn clear_nutation_series()
+{
+    let mut installed = NUTATION_SERIES.write().unwrap_or_else(|e| e.into_inner());
+    *installed = None;
+}
+
+/// IAU 2006/2000A nutation [rad] with the installed series, if any
+pub(super) fn installed_nutation(t: f64) -> Option<(f64, f64)>
+{
+    let installed = NUTATION_SERIES.read().unwrap_or_else(|e| e.into_inner());
+
+    installed.as_ref().map(|series| series.evaluate(t))
+}
+
+/// Fundamental arguments of the IAU 2000A nutation [rad] (IERS Conventions 2010, 5.7):
+/// l, l', F, D, Om, mean longitudes of the planets from Mercury to Neptune, general
+/// precession in longitude
+fn arguments(t: f64) -> [f64; 14]
+{
+    let [l, lp, f, d, om, l_ve, l_e, p_a] = fundamental_arguments(t);
+    let planet = |l0: f64, rate: f64| (l0 + rate * t) % (2.0 * PI);
+
+    [
+        l, lp, f, d, om,
+        planet(4.402608842, 2608.7903141574),
+        l_ve,
+        l_e,
+        planet(6.203480913, 334.0612426700),
+        planet(0.599546497, 52.9690962641),
+        planet(0.874016757, 21.3299104960),
+        planet(5.481293872, 7.4781598567),
+        planet(5.311886287, 3.8133035638),
+        p_a,
+    ]
+}
+
+/// Parse a table of the IERS Conventions. The terms follow a `j = <power>` header, with the
+/// coefficient of the sine before the one of the cosine in both tables (A_i, A"_i in 5.3a
+/// and B"_i, B_i in 5.3b):
+/// `    1   -17206424.18        3338.60    0    0    0    0    1    0    0    0    0    0    0    0    0    0`
+fn parse_table(content: &str) -> Result<Vec<Term>>
+{
+    let mut terms = Vec::new();
+    let mut power = None;
+
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        if let Some(rest) = line.trim().strip_prefix("j =") {
+            power = match rest.split_whitespace().next().map(|p| p.parse::<i32>()) {
+                Some(Ok(p)) => Some(p),
+                _ => return Err(Error::Parse(format!("Invalid power in nutation table: {}", line))),
+            };
+            continue;
+        }
+
+        // Headers and comments do not start with the index of the term
+        if fields.len() != 17 || fields[0].parse::<u32>().is_err() {
+            continue;
+        }
+
+        let power = match power {
+            Some(p) => p,
+            None => return Err(Error::Parse(format!("Nutation term before the 'j =' header: {}", line))),
+        };
+
+        let mut values = [0.0; 16];
+        for (value, field) in values.iter_mut().zip(&fields[1..]) {
+            *value = match field.parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => return Err(Error::Parse(format!("Invalid number '{}' in nutation table: {}", field, line))),
+            };
+        }
+
+        let mut multipliers = [0.0; 14];
+        multipliers.copy_from_slice(&values[2..]);
+
+        terms.push(Term {
+            power,
+            multipliers,
+            sin: values[0],
+            cos: values[1],
+        });
+    }
+
+    if terms.is_empty() {
+        return Err(Error::Parse(String::from("The nutation table has no term")));
+    }
+
+    Ok(terms)
+}
@@ -2,18 +2,23 @@
  * Copyright Contributors to the tardis project
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
-#[macro_use]
-extern crate simple_error;
-
 mod constants;
 mod error;
 
 mod algebra;
+pub mod eclipse;
+pub mod ellipsoid;
+pub mod eop;
 pub mod frames;
 pub mod geometry;
+mod iau2006;
 mod kf5;
+pub mod magnitude;
+pub mod moon;
+pub mod passes;
+pub mod spk;
 pub mod sun;
-mod time;
+pub mod time;
 pub mod tle;
 pub mod traits;
 pub mod utils;
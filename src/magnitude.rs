@@ -0,0 +1,161 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Apparent magnitude of satellites
+ *
+ * The satellite is modelled as a diffuse (Lambertian) sphere. Its intrinsic (standard)
+ * magnitude is the magnitude at 1000 km with a phase angle of 90° (half illuminated):
+ *
+ *   m = m_std + 5 log10(range / 1000 km) - 2.5 log10(F(phase) / F(90°))
+ *   F(phase) = ((π - phase) cos(phase) + sin(phase)) / π
+ *
+ * The intrinsic magnitudes are not part of the TLE. They can be loaded from a table keyed by
+ * NORAD catalog number, like the qs.mag file of Mike McCants' Quicksat.
+ */
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::RwLock;
+use crate::eclipse::{is_sunlit, ShadowModel};
+use crate::frames::{convert_position, GCRF, SEZ};
+use crate::geometry::Vector;
+use crate::sun;
+use crate::traits::Framable;
+use crate::utils::Observation;
+use crate::{Error, Result};
+
+/// Table installed with `set_magnitude_table`
+static MAGNITUDE_TABLE: RwLock<Option<MagnitudeTable>> = RwLock::new(None);
+
+/// # Intrinsic magnitudes of satellites, keyed by NORAD catalog number
+#[derive(Debug, Clone, Default)]
+pub struct MagnitudeTable {
+    magnitudes: HashMap<u32, f64>,
+}
+
+impl MagnitudeTable {
+    pub fn new() -> MagnitudeTable
+    {
+        MagnitudeTable::default()
+    }
+
+    /// Load a table of intrinsic magnitudes
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<MagnitudeTable>
+    {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        MagnitudeTable::parse(&content)
+    }
+
+    /// Parse a table with a NORAD number and a magnitude at the beginning of each line (qs.mag
+    /// format). The lines which do not start with a number are comments.
+    pub fn parse(content: &str) -> Result<MagnitudeTable>
+    {
+        let mut table = MagnitudeTable::new();
+
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+
+            let number = match fields.next().map(|f| f.parse::<u32>()) {
+                Some(Ok(n)) => n,
+                _ => continue,
+            };
+
+            // Entries without magnitude are unknown
+            let magnitude = match fields.next() {
+                Some(f) => match f.parse::<f64>() {
+                    Ok(m) => m,
+                    Err(_) => return Err(Error::Parse(format!("Invalid magnitude '{}' in line: {}", f, line))),
+                },
+                None => continue,
+            };
+
+            table.insert(number, magnitude);
+        }
+
+        Ok(table)
+    }
+
+    pub fn insert(&mut self, number: u32, magnitude: f64)
+    {
+        self.magnitudes.insert(number, magnitude);
+    }
+
+    pub fn get(&self, number: u32) -> Option<f64>
+    {
+        self.magnitudes.get(&number).copied()
+    }
+}
+
+/// Use the given table for the magnitudes of the satellites
+pub fn set_magnitude_table(table: MagnitudeTable)
+{
+    let mut installed = MAGNITUDE_TABLE.write().unwrap_or_else(|e| e.into_inner());
+    *installed = Some(table);
+}
+
+/// Intrinsic magnitude of a satellite in the table currently used
+pub fn intrinsic_magnitude(number: u32) -> Option<f64>
+{
+    let installed = MAGNITUDE_TABLE.read().unwrap_or_else(|e| e.into_inner());
+
+    match &*installed {
+        Some(table) => table.get(number),
+        None => None,
+    }
+}
+
+/// Fraction of the light reflected by a diffuse sphere at the given phase angle [rad],
+/// relatively to a full phase
+pub fn diffuse_sphere_phase(phase_angle: f64) -> f64
+{
+    ((PI - phase_angle) * phase_angle.cos() + phase_angle.sin()) / PI
+}
+
+/// Apparent magnitude of a diffuse sphere of the given intrinsic magnitude, at the given range
+/// [km] and phase angle [rad]
+pub fn apparent_magnitude(intrinsic: f64, range: f64, phase_angle: f64) -> f64
+{
+    let phase = diffuse_sphere_phase(phase_angle) * PI;
+
+    intrinsic + 5.0 * (range / 1000.0).log10() - 2.5 * phase.log10()
+}
+
+/// Apparent magnitude of a satellite from its observation. None if the satellite is in the
+/// umbra of the Earth or fully dark (phase angle of 180°).
+pub fn satellite_magnitude(intrinsic: f64, observation: &Observation) -> Result<Option<f64>>
+{
+    let time = observation.time;
+    let gcrf = GCRF::new(time);
+
+    let mut state = observation.state.clone();
+    state.change_frame(Rc::new(gcrf))?;
+    let p = state.position();
+    let satellite = [p[0], p[1], p[2]];
+
+    if !is_sunlit(satellite, &time, ShadowModel::Conical) {
+        return Ok(None);
+    }
+
+    let site = convert_position(&SEZ::new(&observation.observer, time), &gcrf, [0.0, 0.0, 0.0])?;
+    let sun = sun::position_gcrf(&time);
+
+    // Phase angle: angle at the satellite between the Sun and the observer
+    let to_sun = Vector::from_tuple([0, 1, 2].map(|i| sun[i] - satellite[i]));
+    let to_observer = Vector::from_tuple([0, 1, 2].map(|i| site[i] - satellite[i]));
+    let phase_angle = to_sun.angle(&to_observer).radians();
+
+    if diffuse_sphere_phase(phase_angle) <= 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(apparent_magnitude(intrinsic, observation.look_angles.range, phase_angle)))
+}
@@ -0,0 +1,150 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Moon
+ *
+ * The geocentric position of the Moon is given by the ELP-2000/82 theory in the mean ecliptic
+ * and equinox of date, then rotated to MOD. The topocentric position is seen from the local
+ * horizon of the observer, where the parallax of the Moon reaches 1°.
+ *
+ * The phase angle is the angle at the Moon between the Sun and the Earth, and the illuminated
+ * fraction of the disc is (1 + cos(phase angle)) / 2 (See Meeus, Astronomical Algorithms, 48).
+ */
+
+use std::rc::Rc;
+use chrono::{Duration, NaiveDate};
+use crate::frames::{ecliptic_to_mod, gcrf_to_mod, MOD, SEZ};
+use crate::geometry::{Angle, StateVector, Vector};
+use crate::passes::{PassPredictor, RiseSet};
+use crate::sun;
+use crate::time::{Epoch, TimeScale};
+use crate::traits::{Framable, Observable};
+use crate::utils::{Observation, Observer};
+use crate::Result;
+
+mod elp2000;
+
+/// Elevation of the center of the Moon at moonrise and moonset [°]: 34' of refraction at the
+/// horizon and 15.5' of semi-diameter. The parallax is part of the topocentric elevation.
+pub const MOONRISE_ELEVATION: f64 = -0.825;
+
+/// Apparent visual magnitude of the full Moon at its mean distance
+const FULL_MOON_MAGNITUDE: f64 = -12.73;
+const MEAN_DISTANCE_KM: f64 = 384400.0;
+
+/// Sampling step of the elevation of the Moon
+const STEP_MINUTES: i64 = 10;
+
+pub struct Moon {
+}
+
+impl Moon {
+    pub fn new() -> Moon
+    {
+        Moon {}
+    }
+}
+
+impl Default for Moon {
+    fn default() -> Self {
+        Moon::new()
+    }
+}
+
+/// Geocentric position of the Moon in MOD [km]
+fn position_mod(epoch: &Epoch) -> [f64; 3]
+{
+    let [lon, lat, distance] = elp2000::moon(epoch.julian_centuries(TimeScale::TDB));
+
+    let position = [
+        distance * lat.cos() * lon.cos(),
+        distance * lat.cos() * lon.sin(),
+        distance * lat.sin(),
+    ];
+
+    ecliptic_to_mod(epoch).rotate(position)
+}
+
+/// Geocentric state of the Moon, in MOD
+///
+/// Truncated ELP-2000/82 theory (about 10" in longitude and 4" in latitude).
+pub fn geometric_state(epoch: &Epoch) -> Result<StateVector>
+{
+    // The velocity is the central difference of the position over 2 minutes
+    let step = Duration::seconds(60);
+    let before = position_mod(&(*epoch - step));
+    let after = position_mod(&(*epoch + step));
+    let velocity = [0, 1, 2].map(|i| (after[i] - before[i]) / 120.0);
+
+    StateVector::new(position_mod(epoch), velocity, *epoch, Rc::new(MOD::new(*epoch)))
+}
+
+/// Geocentric position of the Moon in GCRF [km]
+pub fn position_gcrf(epoch: &Epoch) -> [f64; 3]
+{
+    // Transpose of a rotation is its inverse
+    gcrf_to_mod(epoch).transpose().rotate(position_mod(epoch))
+}
+
+/// State of the Moon in the South, East, Zenith frame of the observer
+pub fn topocentric_state(observer: &Observer, epoch: &Epoch) -> Result<StateVector>
+{
+    let mut state = geometric_state(epoch)?;
+    state.change_frame(Rc::new(SEZ::new(observer, *epoch)))?;
+
+    Ok(state)
+}
+
+/// Angle at the Moon between the Sun and the Earth: 0 at full Moon, 180° at new Moon
+pub fn phase_angle(epoch: &Epoch) -> Angle
+{
+    let moon = position_gcrf(epoch);
+    let sun = sun::position_gcrf(epoch);
+
+    let to_sun = Vector::from_tuple([0, 1, 2].map(|i| sun[i] - moon[i]));
+    let to_earth = Vector::from_tuple(moon.map(|x| -x));
+
+    to_sun.angle(&to_earth)
+}
+
+/// Illuminated fraction of the disc of the Moon, from 0 (new Moon) to 1 (full Moon)
+pub fn illuminated_fraction(epoch: &Epoch) -> f64
+{
+    (1.0 + phase_angle(epoch).radians().cos()) / 2.0
+}
+
+/// Moonrise and moonset for the observer during the given date. The day is the local mean
+/// solar day, from midnight at the longitude of the observer. The Moon rises about 50 minutes
+/// later each day, so some days have no moonrise or no moonset.
+pub fn rise_set(observer: &Observer, date: NaiveDate) -> Result<RiseSet>
+{
+    let (start, end) = sun::local_day(observer, date);
+
+    PassPredictor::new(&Moon::new(), observer)
+        .with_min_elevation(MOONRISE_ELEVATION)
+        .with_step(Duration::minutes(STEP_MINUTES))
+        .rise_set(start, end)
+}
+
+impl Observable for Moon {
+    fn name(&self) -> String {
+        String::from("Moon")
+    }
+
+    fn observation(&self, observer: &Observer) -> Result<Observation> {
+        self.observation_at(observer, Epoch::now())
+    }
+
+    fn observation_at(&self, observer: &Observer, time: Epoch) -> Result<Observation> {
+        let mut observation = Observation::new(observer, geometric_state(&time)?)?;
+
+        // Magnitude of the Moon from its phase angle [°] (See Allen, Astrophysical Quantities)
+        let phase = phase_angle(&time).degrees();
+        observation.magnitude = Some(FULL_MOON_MAGNITUDE + 0.026 * phase + 4e-9 * phase.powi(4)
+            + 5.0 * (observation.look_angles.range / MEAN_DISTANCE_KM).log10());
+
+        Ok(observation)
+    }
+}
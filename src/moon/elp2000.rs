@@ -0,0 +1,208 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Geocentric position of the Moon, ELP-2000/82 theory
+ *
+ * Truncated series of Meeus, Astronomical Algorithms, chapter 47 (about 10" in longitude and
+ * 4" in latitude). The coordinates are referred to the mean ecliptic and equinox of date.
+ *
+ * Each term is a multiple of the Delaunay arguments D (mean elongation of the Moon), M (mean
+ * anomaly of the Sun), M' (mean anomaly of the Moon) and F (argument of latitude of the Moon).
+ * The terms in M are multiplied by E = 1 - 0.002516 T - 0.0000074 T², for the decreasing
+ * eccentricity of the orbit of the Earth.
+ */
+
+use crate::algebra::evalpoly;
+
+/// Mean distance of the Moon [km]
+const MEAN_DISTANCE_KM: f64 = 385000.56;
+
+/// Multiples of D, M, M', F, coefficients of the sine of the longitude [1e-6 °] and of the
+/// cosine of the distance [1e-3 km]
+const LONGITUDE_DISTANCE: [[f64; 6]; 60] = [
+    [0.0, 0.0, 1.0, 0.0, 6288774.0, -20905355.0],
+    [2.0, 0.0, -1.0, 0.0, 1274027.0, -3699111.0],
+    [2.0, 0.0, 0.0, 0.0, 658314.0, -2955968.0],
+    [0.0, 0.0, 2.0, 0.0, 213618.0, -569925.0],
+    [0.0, 1.0, 0.0, 0.0, -185116.0, 48888.0],
+    [0.0, 0.0, 0.0, 2.0, -114332.0, -3149.0],
+    [2.0, 0.0, -2.0, 0.0, 58793.0, 246158.0],
+    [2.0, -1.0, -1.0, 0.0, 57066.0, -152138.0],
+    [2.0, 0.0, 1.0, 0.0, 53322.0, -170733.0],
+    [2.0, -1.0, 0.0, 0.0, 45758.0, -204586.0],
+    [0.0, 1.0, -1.0, 0.0, -40923.0, -129620.0],
+    [1.0, 0.0, 0.0, 0.0, -34720.0, 108743.0],
+    [0.0, 1.0, 1.0, 0.0, -30383.0, 104755.0],
+    [2.0, 0.0, 0.0, -2.0, 15327.0, 10321.0],
+    [0.0, 0.0, 1.0, 2.0, -12528.0, 0.0],
+    [0.0, 0.0, 1.0, -2.0, 10980.0, 79661.0],
+    [4.0, 0.0, -1.0, 0.0, 10675.0, -34782.0],
+    [0.0, 0.0, 3.0, 0.0, 10034.0, -23210.0],
+    [4.0, 0.0, -2.0, 0.0, 8548.0, -21636.0],
+    [2.0, 1.0, -1.0, 0.0, -7888.0, 24208.0],
+    [2.0, 1.0, 0.0, 0.0, -6766.0, 30824.0],
+    [1.0, 0.0, -1.0, 0.0, -5163.0, -8379.0],
+    [1.0, 1.0, 0.0, 0.0, 4987.0, -16675.0],
+    [2.0, -1.0, 1.0, 0.0, 4036.0, -12831.0],
+    [2.0, 0.0, 2.0, 0.0, 3994.0, -10445.0],
+    [4.0, 0.0, 0.0, 0.0, 3861.0, -11650.0],
+    [2.0, 0.0, -3.0, 0.0, 3665.0, 14403.0],
+    [0.0, 1.0, -2.0, 0.0, -2689.0, -7003.0],
+    [2.0, 0.0, -1.0, 2.0, -2602.0, 0.0],
+    [2.0, -1.0, -2.0, 0.0, 2390.0, 10056.0],
+    [1.0, 0.0, 1.0, 0.0, -2348.0, 6322.0],
+    [2.0, -2.0, 0.0, 0.0, 2236.0, -9884.0],
+    [0.0, 1.0, 2.0, 0.0, -2120.0, 5751.0],
+    [0.0, 2.0, 0.0, 0.0, -2069.0, 0.0],
+    [2.0, -2.0, -1.0, 0.0, 2048.0, -4950.0],
+    [2.0, 0.0, 1.0, -2.0, -1773.0, 4130.0],
+    [2.0, 0.0, 0.0, 2.0, -1595.0, 0.0],
+    [4.0, -1.0, -1.0, 0.0, 1215.0, -3958.0],
+    [0.0, 0.0, 2.0, 2.0, -1110.0, 0.0],
+    [3.0, 0.0, -1.0, 0.0, -892.0, 3258.0],
+    [2.0, 1.0, 1.0, 0.0, -810.0, 2616.0],
+    [4.0, -1.0, -2.0, 0.0, 759.0, -1897.0],
+    [0.0, 2.0, -1.0, 0.0, -713.0, -2117.0],
+    [2.0, 2.0, -1.0, 0.0, -700.0, 2354.0],
+    [2.0, 1.0, -2.0, 0.0, 691.0, 0.0],
+    [2.0, -1.0, 0.0, -2.0, 596.0, 0.0],
+    [4.0, 0.0, 1.0, 0.0, 549.0, -1423.0],
+    [0.0, 0.0, 4.0, 0.0, 537.0, -1117.0],
+    [4.0, -1.0, 0.0, 0.0, 520.0, -1571.0],
+    [1.0, 0.0, -2.0, 0.0, -487.0, -1739.0],
+    [2.0, 1.0, 0.0, -2.0, -399.0, 0.0],
+    [0.0, 0.0, 2.0, -2.0, -381.0, -4421.0],
+    [1.0, 1.0, 1.0, 0.0, 351.0, 0.0],
+    [3.0, 0.0, -2.0, 0.0, -340.0, 0.0],
+    [4.0, 0.0, -3.0, 0.0, 330.0, 0.0],
+    [2.0, -1.0, 2.0, 0.0, 327.0, 0.0],
+    [0.0, 2.0, 1.0, 0.0, -323.0, 1165.0],
+    [1.0, 1.0, -1.0, 0.0, 299.0, 0.0],
+    [2.0, 0.0, 3.0, 0.0, 294.0, 0.0],
+    [2.0, 0.0, -1.0, -2.0, 0.0, 8752.0],
+];
+
+/// Multiples of D, M, M', F and coefficient of the sine of the latitude [1e-6 °]
+const LATITUDE: [[f64; 5]; 60] = [
+    [0.0, 0.0, 0.0, 1.0, 5128122.0],
+    [0.0, 0.0, 1.0, 1.0, 280602.0],
+    [0.0, 0.0, 1.0, -1.0, 277693.0],
+    [2.0, 0.0, 0.0, -1.0, 173237.0],
+    [2.0, 0.0, -1.0, 1.0, 55413.0],
+    [2.0, 0.0, -1.0, -1.0, 46271.0],
+    [2.0, 0.0, 0.0, 1.0, 32573.0],
+    [0.0, 0.0, 2.0, 1.0, 17198.0],
+    [2.0, 0.0, 1.0, -1.0, 9266.0],
+    [0.0, 0.0, 2.0, -1.0, 8822.0],
+    [2.0, -1.0, 0.0, -1.0, 8216.0],
+    [2.0, 0.0, -2.0, -1.0, 4324.0],
+    [2.0, 0.0, 1.0, 1.0, 4200.0],
+    [2.0, 1.0, 0.0, -1.0, -3359.0],
+    [2.0, -1.0, -1.0, 1.0, 2463.0],
+    [2.0, -1.0, 0.0, 1.0, 2211.0],
+    [2.0, -1.0, -1.0, -1.0, 2065.0],
+    [0.0, 1.0, -1.0, -1.0, -1870.0],
+    [4.0, 0.0, -1.0, -1.0, 1828.0],
+    [0.0, 1.0, 0.0, 1.0, -1794.0],
+    [0.0, 0.0, 0.0, 3.0, -1749.0],
+    [0.0, 1.0, -1.0, 1.0, -1565.0],
+    [1.0, 0.0, 0.0, 1.0, -1491.0],
+    [0.0, 1.0, 1.0, 1.0, -1475.0],
+    [0.0, 1.0, 1.0, -1.0, -1410.0],
+    [0.0, 1.0, 0.0, -1.0, -1344.0],
+    [1.0, 0.0, 0.0, -1.0, -1335.0],
+    [0.0, 0.0, 3.0, 1.0, 1107.0],
+    [4.0, 0.0, 0.0, -1.0, 1021.0],
+    [4.0, 0.0, -1.0, 1.0, 833.0],
+    [0.0, 0.0, 1.0, -3.0, 777.0],
+    [4.0, 0.0, -2.0, 1.0, 671.0],
+    [2.0, 0.0, 0.0, -3.0, 607.0],
+    [2.0, 0.0, 2.0, -1.0, 596.0],
+    [2.0, -1.0, 1.0, -1.0, 491.0],
+    [2.0, 0.0, -2.0, 1.0, -451.0],
+    [0.0, 0.0, 3.0, -1.0, 439.0],
+    [2.0, 0.0, 2.0, 1.0, 422.0],
+    [2.0, 0.0, -3.0, -1.0, 421.0],
+    [2.0, 1.0, -1.0, 1.0, -366.0],
+    [2.0, 1.0, 0.0, 1.0, -351.0],
+    [4.0, 0.0, 0.0, 1.0, 331.0],
+    [2.0, -1.0, 1.0, 1.0, 315.0],
+    [2.0, -2.0, 0.0, -1.0, 302.0],
+    [0.0, 0.0, 1.0, 3.0, -283.0],
+    [2.0, 1.0, 1.0, -1.0, -229.0],
+    [1.0, 1.0, 0.0, -1.0, 223.0],
+    [1.0, 1.0, 0.0, 1.0, 223.0],
+    [0.0, 1.0, -2.0, -1.0, -220.0],
+    [2.0, 1.0, -1.0, -1.0, -220.0],
+    [1.0, 0.0, 1.0, 1.0, -185.0],
+    [2.0, -1.0, -2.0, -1.0, 181.0],
+    [0.0, 1.0, 2.0, 1.0, -177.0],
+    [4.0, 0.0, -2.0, -1.0, 176.0],
+    [4.0, -1.0, -1.0, -1.0, 166.0],
+    [1.0, 0.0, 1.0, -1.0, -164.0],
+    [4.0, 0.0, 1.0, -1.0, 132.0],
+    [1.0, 0.0, -1.0, -1.0, -119.0],
+    [4.0, -1.0, 0.0, -1.0, 115.0],
+    [2.0, -2.0, 0.0, 1.0, 107.0],
+];
+
+/// Geocentric ecliptic longitude [rad], latitude [rad] and distance [km] of the Moon at T
+/// Julian centuries (TDB) since J2000
+pub(super) fn moon(t: f64) -> [f64; 3]
+{
+    let l = evalpoly(t, vec![218.3164477, 481267.88123421, -0.0015786, 1.0 / 538841.0, -1.0 / 65194000.0]).to_radians();
+    let d = evalpoly(t, vec![297.8501921, 445267.1114034, -0.0018819, 1.0 / 545868.0, -1.0 / 113065000.0]).to_radians();
+    let m = evalpoly(t, vec![357.5291092, 35999.0502909, -0.0001536, 1.0 / 24490000.0]).to_radians();
+    let mp = evalpoly(t, vec![134.9633964, 477198.8675055, 0.0087414, 1.0 / 69699.0, -1.0 / 14712000.0]).to_radians();
+    let f = evalpoly(t, vec![93.2720950, 483202.0175233, -0.0036539, -1.0 / 3526000.0, 1.0 / 863310000.0]).to_radians();
+
+    // Action of Venus (a1), of Jupiter (a2) and flattening of the Earth (a3)
+    let a1 = (119.75 + 131.849 * t).to_radians();
+    let a2 = (53.09 + 479264.290 * t).to_radians();
+    let a3 = (313.45 + 481266.484 * t).to_radians();
+
+    let e = evalpoly(t, vec![1.0, -0.002516, -0.0000074]);
+    let eccentricity = |multiple: f64| e.powi(multiple.abs() as i32);
+
+    let mut sum_l = 3958.0 * a1.sin() + 1962.0 * (l - f).sin() + 318.0 * a2.sin();
+    let mut sum_r = 0.0;
+    for [cd, cm, cmp, cf, cl, cr] in LONGITUDE_DISTANCE {
+        let argument = cd * d + cm * m + cmp * mp + cf * f;
+        sum_l += cl * eccentricity(cm) * argument.sin();
+        sum_r += cr * eccentricity(cm) * argument.cos();
+    }
+
+    let mut sum_b = -2235.0 * l.sin() + 382.0 * a3.sin() + 175.0 * (a1 - f).sin() + 175.0 * (a1 + f).sin()
+        + 127.0 * (l - mp).sin() - 115.0 * (l + mp).sin();
+    for [cd, cm, cmp, cf, cb] in LATITUDE {
+        let argument = cd * d + cm * m + cmp * mp + cf * f;
+        sum_b += cb * eccentricity(cm) * argument.sin();
+    }
+
+    [
+        l + (sum_l * 1e-6).to_radians(),
+        (sum_b * 1e-6).to_radians(),
+        MEAN_DISTANCE_KM + sum_r * 1e-3,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn meeus_example()
+    {
+        // Example 47.a of Meeus: 1992 April 12 at 0h TD, given to 1e-6 ° and 0.1 km
+        let t = (2448724.5 - 2451545.0) / 36525.0;
+        let [lon, lat, distance] = moon(t);
+
+        let lon = lon.to_degrees().rem_euclid(360.0);
+        assert!((lon - 133.162655).abs() < 1e-6, "{}", lon);
+        assert!((lat.to_degrees() + 3.229126).abs() < 1e-6, "{}", lat.to_degrees());
+        assert!((distance - 368409.7).abs() < 0.1, "{}", distance);
+    }
+}
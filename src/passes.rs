@@ -0,0 +1,382 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Pass prediction
+ *
+ * The elevation of the object is sampled over the time window. The rise and set times are the
+ * roots of elevation - minimum elevation, refined by bisection between the samples, and the
+ * culmination is refined by a golden section search. Short passes whose culmination falls
+ * between 2 samples are found from the local maxima of the samples.
+ *
+ * A pass is visible to the eye when the object is lit by the Sun, even partly in the penumbra,
+ * while the sky of the observer is dark. The changes of visibility during a pass are found
+ * the same way.
+ */
+
+use std::rc::Rc;
+use chrono::Duration;
+use crate::algebra::{bisect, seconds};
+use crate::eclipse::{is_sunlit, ShadowModel};
+use crate::frames::GCRF;
+use crate::geometry::Angle;
+use crate::sun::{Sun, Twilight};
+use crate::time::Epoch;
+use crate::traits::{Framable, Observable};
+use crate::utils::Observer;
+use crate::Result;
+
+/// Accuracy of the event times [s]
+const TIME_TOLERANCE: f64 = 0.01;
+
+/// Sampling step of the visibility during a pass [s]
+const VISIBILITY_STEP: f64 = 10.0;
+
+/// # Visibility of an object to the eye
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// The object is lit by the Sun, above the minimum elevation, and the sky is dark
+    Visible,
+    /// The object is below the minimum elevation
+    BelowHorizon,
+    /// The sky of the observer is not dark enough
+    Daylight,
+    /// The object is in the shadow of the Earth
+    Eclipsed,
+}
+
+/// # Position of the object in the sky at an event of a pass
+#[derive(Clone, Copy)]
+pub struct PassEvent {
+    pub time: Epoch,
+    pub azimuth: Angle,
+    pub elevation: Angle,
+}
+
+/// # Pass of an object above the minimum elevation
+/// The rise (AOS) is None if the pass started before the time window, and the set (LOS) is None
+/// if it ends after the time window.
+#[derive(Clone, Copy)]
+pub struct Pass {
+    pub rise: Option<PassEvent>,
+    pub culmination: PassEvent,
+    pub set: Option<PassEvent>,
+}
+
+/// # Part of a pass where the object is visible
+/// `before` and `after` give why the object is not visible before and after the segment.
+#[derive(Clone, Copy)]
+pub struct VisibleSegment {
+    pub start: PassEvent,
+    pub end: PassEvent,
+    pub before: Visibility,
+    pub after: Visibility,
+}
+
+/// # Pass with at least one visible segment
+#[derive(Clone)]
+pub struct VisiblePass {
+    pub pass: Pass,
+    pub segments: Vec<VisibleSegment>,
+}
+
+/// # Crossings of the minimum elevation by an object over a time window
+#[derive(Clone, Copy)]
+pub enum RiseSet {
+    /// The object rises or sets during the time window. The rise is None if the object is
+    /// above the minimum elevation at the beginning of the window, and the set is None if it
+    /// is still above at the end.
+    Crossings {
+        rise: Option<PassEvent>,
+        set: Option<PassEvent>,
+    },
+    /// The object stays above the minimum elevation (polar day for the Sun)
+    AlwaysAbove,
+    /// The object stays below the minimum elevation (polar night for the Sun)
+    AlwaysBelow,
+}
+
+impl Pass {
+    /// Maximum elevation of the object during the pass
+    pub fn max_elevation(&self) -> Angle
+    {
+        self.culmination.elevation
+    }
+}
+
+/// # Search of the passes of an object over an observer
+pub struct PassPredictor<'a> {
+    observable: &'a dyn Observable,
+    observer: Observer,
+    min_elevation: f64,
+    step: Duration,
+    darkness: Twilight,
+    shadow_model: ShadowModel,
+}
+
+impl<'a> PassPredictor<'a> {
+    /// Passes above the horizon, with the elevation sampled every minute (suited to LEO
+    /// satellites, whose passes last a few minutes)
+    pub fn new(observable: &'a dyn Observable, observer: &Observer) -> PassPredictor<'a>
+    {
+        PassPredictor {
+            observable,
+            observer: *observer,
+            min_elevation: 0.0,
+            step: Duration::seconds(60),
+            darkness: Twilight::Nautical,
+            shadow_model: ShadowModel::Conical,
+        }
+    }
+
+    /// Only keep the part of the passes above the given elevation [°]
+    pub fn with_min_elevation(mut self, degrees: f64) -> PassPredictor<'a>
+    {
+        self.min_elevation = degrees;
+        self
+    }
+
+    /// Sampling step of the elevation. Passes shorter than the step may be missed.
+    pub fn with_step(mut self, step: Duration) -> PassPredictor<'a>
+    {
+        self.step = step;
+        self
+    }
+
+    /// Darkness of the sky needed to see the object (nautical twilight by default)
+    pub fn with_darkness(mut self, darkness: Twilight) -> PassPredictor<'a>
+    {
+        self.darkness = darkness;
+        self
+    }
+
+    /// Model of the shadow of the Earth (conical by default). The object is visible in the
+    /// penumbra.
+    pub fn with_shadow_model(mut self, model: ShadowModel) -> PassPredictor<'a>
+    {
+        self.shadow_model = model;
+        self
+    }
+
+    /// Visibility of the object at the given time
+    pub fn visibility(&self, time: Epoch) -> Result<Visibility>
+    {
+        let mut observation = self.observable.observation_at(&self.observer, time)?;
+
+        if observation.look_angles.elevation.degrees() < self.min_elevation {
+            return Ok(Visibility::BelowHorizon);
+        }
+
+        let sun = Sun::new().observation_at(&self.observer, time)?;
+        if sun.look_angles.elevation.degrees() > self.darkness.sun_elevation() {
+            return Ok(Visibility::Daylight);
+        }
+
+        observation.state.change_frame(Rc::new(GCRF::new(time)))?;
+        let p = observation.state.position();
+        if !is_sunlit([p[0], p[1], p[2]], &time, self.shadow_model) {
+            return Ok(Visibility::Eclipsed);
+        }
+
+        Ok(Visibility::Visible)
+    }
+
+    /// Passes over the time window with the segments where the object is visible. The passes
+    /// without visible segment are skipped.
+    pub fn visible_passes(&self, start: Epoch, end: Epoch) -> Result<Vec<VisiblePass>>
+    {
+        let at = |t: f64| start + Duration::nanoseconds((t * 1e9).round() as i64);
+        let f = |t: f64| self.visibility(at(t));
+        let duration = seconds(end - start);
+        let mut visible_passes = Vec::new();
+
+        for pass in self.passes(start, end)? {
+            // Sample inside the pass, where the object is above the minimum elevation
+            let first = pass.rise.map_or(0.0, |e| seconds(e.time - start)) + TIME_TOLERANCE;
+            let last = pass.set.map_or(duration, |e| seconds(e.time - start)) - TIME_TOLERANCE;
+
+            let mut samples = Vec::new();
+            let mut t = first;
+            while t < last {
+                samples.push((t, f(t)?));
+                t += VISIBILITY_STEP;
+            }
+            samples.push((last, f(last)?));
+
+            // Boundaries of the visible segments, with the visibility on the other side
+            let mut bounds = Vec::new();
+            let mut segment_start = match samples[0].1 {
+                Visibility::Visible => Some((first, f(first - 2.0 * TIME_TOLERANCE)?)),
+                _ => None,
+            };
+
+            for i in 1..samples.len() {
+                let (t0, v0) = samples[i - 1];
+                let (t1, v1) = samples[i];
+                if v0 == v1 {
+                    continue;
+                }
+
+                let change = bisect(&|t: f64| Ok(if f(t)? == v0 { -1.0 } else { 1.0 }), t0, t1, -1.0, TIME_TOLERANCE)?;
+                if v1 == Visibility::Visible {
+                    segment_start = Some((change, v0));
+                } else if let Some((s, before)) = segment_start.take() {
+                    bounds.push((s, before, change, v1));
+                }
+            }
+
+            if let Some((s, before)) = segment_start {
+                bounds.push((s, before, last, f(last + 2.0 * TIME_TOLERANCE)?));
+            }
+
+            let mut segments = Vec::new();
+            for (s, before, e, after) in bounds {
+                segments.push(VisibleSegment {
+                    start: self.event(at(s))?,
+                    end: self.event(at(e))?,
+                    before,
+                    after,
+                });
+            }
+
+            if !segments.is_empty() {
+                visible_passes.push(VisiblePass {
+                    pass,
+                    segments,
+                });
+            }
+        }
+
+        Ok(visible_passes)
+    }
+
+    /// Rise and set of the object over the time window. The first rise and the last set are
+    /// kept if the object crosses the minimum elevation several times.
+    pub fn rise_set(&self, start: Epoch, end: Epoch) -> Result<RiseSet>
+    {
+        let passes = self.passes(start, end)?;
+
+        Ok(match passes.as_slice() {
+            [] => RiseSet::AlwaysBelow,
+            [pass] if pass.rise.is_none() && pass.set.is_none() => RiseSet::AlwaysAbove,
+            _ => RiseSet::Crossings {
+                rise: passes.iter().find_map(|p| p.rise),
+                set: passes.iter().rev().find_map(|p| p.set),
+            },
+        })
+    }
+
+    /// Passes over the time window, sorted by time
+    pub fn passes(&self, start: Epoch, end: Epoch) -> Result<Vec<Pass>>
+    {
+        let duration = seconds(end - start);
+        let step = seconds(self.step);
+        let at = |t: f64| start + Duration::nanoseconds((t * 1e9).round() as i64);
+        let f = |t: f64| -> Result<f64> { Ok(self.event(at(t))?.elevation.degrees() - self.min_elevation) };
+
+        let mut samples = Vec::new();
+        let mut t = 0.0;
+        while t < duration {
+            samples.push((t, f(t)?));
+            t += step;
+        }
+        samples.push((duration, f(duration)?));
+
+        // Times where the object goes above and below the minimum elevation
+        let mut crossings: Vec<(f64, bool)> = Vec::new();
+        for i in 1..samples.len() {
+            let (t0, f0) = samples[i - 1];
+            let (t1, f1) = samples[i];
+
+            if (f0 < 0.0) != (f1 < 0.0) {
+                crossings.push((bisect(&f, t0, t1, f0, TIME_TOLERANCE)?, f1 >= 0.0));
+            } else if f0 < 0.0 && i + 1 < samples.len() && f1 > f0 && f1 >= samples[i + 1].1 {
+                // Maximum between the samples: the object may briefly rise
+                let (t2, _) = samples[i + 1];
+                let top = golden_section(&f, t0, t2)?;
+                let f_top = f(top)?;
+
+                if f_top >= 0.0 {
+                    crossings.push((bisect(&f, t0, top, f0, TIME_TOLERANCE)?, true));
+                    crossings.push((bisect(&f, top, t2, f_top, TIME_TOLERANCE)?, false));
+                }
+            }
+        }
+
+        // Close the passes in progress at the beginning or at the end of the window
+        let mut bounds = Vec::new();
+        let mut rise = if samples[0].1 >= 0.0 { Some(None) } else { None };
+        for (t, rising) in crossings {
+            match (rising, rise) {
+                (true, None) => rise = Some(Some(t)),
+                (false, Some(r)) => {
+                    bounds.push((r, Some(t)));
+                    rise = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(r) = rise {
+            bounds.push((r, None));
+        }
+
+        let mut passes = Vec::new();
+        for (rise, set) in bounds {
+            let top = golden_section(&f, rise.unwrap_or(0.0), set.unwrap_or(duration))?;
+
+            passes.push(Pass {
+                rise: match rise {
+                    Some(t) => Some(self.event(at(t))?),
+                    None => None,
+                },
+                culmination: self.event(at(top))?,
+                set: match set {
+                    Some(t) => Some(self.event(at(t))?),
+                    None => None,
+                },
+            });
+        }
+
+        Ok(passes)
+    }
+
+    fn event(&self, time: Epoch) -> Result<PassEvent>
+    {
+        let observation = self.observable.observation_at(&self.observer, time)?;
+
+        Ok(PassEvent {
+            time,
+            azimuth: observation.look_angles.azimuth,
+            elevation: observation.look_angles.elevation,
+        })
+    }
+}
+
+/// Maximum of f in [a, b], f being unimodal on the interval
+fn golden_section<F: Fn(f64) -> Result<f64>>(f: &F, mut a: f64, mut b: f64) -> Result<f64>
+{
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let mut fc = f(c)?;
+    let mut fd = f(d)?;
+
+    while b - a > TIME_TOLERANCE {
+        if fc > fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c)?;
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d)?;
+        }
+    }
+
+    Ok((a + b) / 2.0)
+}
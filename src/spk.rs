@@ -0,0 +1,480 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* JPL SPK ephemerides
+ *
+ * Binary SPK kernels (e.g. DE440) are DAF files made of records of 1024 bytes. The file record
+ * gives the byte order and the first of the linked summary records, which describe the
+ * segments. A segment gives the state of a target body relatively to a center body over a time
+ * span, in TDB seconds since J2000. When several segments cover the same body and epoch, the
+ * last one in the file is used.
+ *
+ * Segments of type 2 (position) and 3 (position and velocity) are Chebyshev polynomials over
+ * intervals of fixed length. Each record holds the middle and the radius of its interval, then
+ * the coefficients of each component. The velocity of a type 2 segment is the derivative of
+ * the polynomials.
+ * See the SPK Required Reading of NAIF.
+ *
+ * The bodies are identified by their NAIF ID codes, and the states are in ICRF [km, km/s]. The
+ * segments are given in the J2000 frame (NAIF frame 1, ICRF for the JPL ephemerides) or in the
+ * ecliptic of J2000 (NAIF frame 17), rotated to ICRF.
+ *
+ * A kernel installed with `set_ephemeris` gives the position of the Earth relatively to the
+ * solar system barycenter to the ICRS frame.
+ */
+
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::RwLock;
+use crate::constants::SPEED_OF_LIGHT_KM_S;
+use crate::frames::GCRF;
+use crate::geometry::{Matrix, RotationAxis, StateVector};
+use crate::time::{Epoch, JD_J2000, TimeScale};
+use crate::traits::Observable;
+use crate::utils::{Observation, Observer};
+use crate::{Error, Result};
+
+pub const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+pub const EARTH_MOON_BARYCENTER: i32 = 3;
+pub const SUN: i32 = 10;
+pub const MOON: i32 = 301;
+pub const EARTH: i32 = 399;
+
+/// Kernel installed with `set_ephemeris`
+static EPHEMERIS: RwLock<Option<SpkFile>> = RwLock::new(None);
+
+const RECORD_LENGTH: usize = 1024;
+const WORD_LENGTH: usize = 8;
+
+/// Summaries in a summary record: 3 control words, then 5 words per summary
+const MAX_SUMMARIES: usize = 25;
+
+/// NAIF ID codes of the frames of the segments
+const J2000_FRAME: i32 = 1;
+const ECLIPJ2000_FRAME: i32 = 17;
+
+/// Obliquity of the ecliptic of J2000 in NAIF ECLIPJ2000 [rad]
+const ECLIPJ2000_OBLIQUITY: f64 = 84381.448 * ARCSEC_TO_RAD;
+const ARCSEC_TO_RAD: f64 = PI / 648000.0;
+
+/// Longest chain of centers from a body to the solar system barycenter
+const MAX_CHAIN: usize = 16;
+
+/// Name of a body from its NAIF ID code
+pub fn naif_name(id: i32) -> String
+{
+    let name = match id {
+        0 => "Solar System Barycenter",
+        1 => "Mercury Barycenter",
+        2 => "Venus Barycenter",
+        3 => "Earth-Moon Barycenter",
+        4 => "Mars Barycenter",
+        5 => "Jupiter Barycenter",
+        6 => "Saturn Barycenter",
+        7 => "Uranus Barycenter",
+        8 => "Neptune Barycenter",
+        9 => "Pluto Barycenter",
+        10 => "Sun",
+        199 => "Mercury",
+        299 => "Venus",
+        301 => "Moon",
+        399 => "Earth",
+        499 => "Mars",
+        599 => "Jupiter",
+        699 => "Saturn",
+        799 => "Uranus",
+        899 => "Neptune",
+        999 => "Pluto",
+        _ => return format!("NAIF {}", id),
+    };
+
+    String::from(name)
+}
+
+/// TDB seconds since J2000
+fn tdb_seconds(epoch: &Epoch) -> f64
+{
+    let (day, fraction) = epoch.julian_day_parts(TimeScale::TDB);
+
+    ((day - JD_J2000) + fraction) * 86400.0
+}
+
+/// Segment of an SPK file
+struct Segment {
+    target: i32,
+    center: i32,
+    frame: i32,
+    data_type: i32,
+    /// Time span [s TDB since J2000]
+    start: f64,
+    end: f64,
+    /// Address of the first word of the data (1-based)
+    address: usize,
+    /// Start of the first interval [s TDB since J2000]
+    init: f64,
+    interval_length: f64,
+    /// Number of words in a record
+    record_size: usize,
+    records: usize,
+}
+
+/// # Binary SPK ephemeris file
+pub struct SpkFile {
+    data: Vec<u8>,
+    little_endian: bool,
+    segments: Vec<Segment>,
+}
+
+impl SpkFile {
+    /// Load an SPK file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<SpkFile>
+    {
+        let data = match fs::read(path) {
+            Ok(d) => d,
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        SpkFile::parse(data)
+    }
+
+    /// Parse the content of an SPK file
+    pub fn parse(data: Vec<u8>) -> Result<SpkFile>
+    {
+        if data.len() < RECORD_LENGTH || !data.starts_with(b"DAF/SPK") {
+            return Err(Error::Parse(String::from("Not a binary SPK file")));
+        }
+
+        let little_endian = match &data[88..96] {
+            b"LTL-IEEE" => true,
+            b"BIG-IEEE" => false,
+            _ => return Err(Error::Parse(String::from("Unsupported binary format of the SPK file"))),
+        };
+
+        let mut file = SpkFile {
+            data,
+            little_endian,
+            segments: Vec::new(),
+        };
+
+        let nd = file.int_at(8)?;
+        let ni = file.int_at(12)?;
+        if nd != 2 || ni != 6 {
+            return Err(Error::Parse(format!("Invalid summary format of the SPK file: ND = {}, NI = {}", nd, ni)));
+        }
+
+        // A summary has 2 doubles and 6 integers packed in 3 words
+        let summary_length = 5 * WORD_LENGTH;
+
+        // The summary records are a linked list: a loop would visit more records than the file has
+        let mut record = file.int_at(76)?;
+        let mut visited = 0;
+        while record != 0 {
+            visited += 1;
+            if visited > file.data.len() / RECORD_LENGTH {
+                return Err(Error::Parse(String::from("Loop in the summary records of the SPK file")));
+            }
+
+            let base = usize::try_from(record).ok()
+                .and_then(|r| r.checked_sub(1))
+                .and_then(|r| r.checked_mul(RECORD_LENGTH));
+            let base = match base {
+                Some(b) => b,
+                None => return Err(Error::Parse(format!("Invalid summary record {} in the SPK file", record))),
+            };
+            let next = file.double_at(base)?;
+            let count = file.double_at(base + 2 * WORD_LENGTH)?;
+            if !(0.0..=MAX_SUMMARIES as f64).contains(&count) {
+                return Err(Error::Parse(format!("Invalid number of summaries in the SPK file: {}", count)));
+            }
+            let count = count as usize;
+
+            for i in 0..count {
+                let offset = base + 3 * WORD_LENGTH + i * summary_length;
+                let ints = offset + 2 * WORD_LENGTH;
+
+                let segment = file.segment(
+                    file.double_at(offset)?,
+                    file.double_at(offset + WORD_LENGTH)?,
+                    [0, 1, 2, 3, 4, 5].map(|j| file.int_at(ints + 4 * j)),
+                )?;
+                file.segments.push(segment);
+            }
+
+            record = next as i32;
+        }
+
+        Ok(file)
+    }
+
+    /// NAIF ID codes of the bodies of the file, sorted
+    pub fn bodies(&self) -> Vec<i32>
+    {
+        let mut bodies: Vec<i32> = self.segments.iter().map(|s| s.target).collect();
+        bodies.sort();
+        bodies.dedup();
+
+        bodies
+    }
+
+    /// Body of the file, as an `Observable`
+    pub fn body(&self, target: i32) -> Result<SpkBody<'_>>
+    {
+        if !self.segments.iter().any(|s| s.target == target) {
+            return Err(Error::Ephemeris(format!("No segment for {} in the SPK file", naif_name(target))));
+        }
+
+        Ok(SpkBody {
+            kernel: self,
+            target,
+        })
+    }
+
+    /// Position [km] and velocity [km/s] in ICRF of the target relatively to the center
+    pub fn state(&self, target: i32, center: i32, epoch: &Epoch) -> Result<([f64; 3], [f64; 3])>
+    {
+        let seconds = tdb_seconds(epoch);
+        let (p_target, v_target) = self.barycentric_state(target, seconds)?;
+        let (p_center, v_center) = self.barycentric_state(center, seconds)?;
+
+        Ok((
+            [0, 1, 2].map(|i| p_target[i] - p_center[i]),
+            [0, 1, 2].map(|i| v_target[i] - v_center[i]),
+        ))
+    }
+
+    /// State relatively to the solar system barycenter, following the centers of the segments
+    fn barycentric_state(&self, mut body: i32, seconds: f64) -> Result<([f64; 3], [f64; 3])>
+    {
+        let mut position = [0.0; 3];
+        let mut velocity = [0.0; 3];
+
+        for _ in 0..MAX_CHAIN {
+            if body == SOLAR_SYSTEM_BARYCENTER {
+                return Ok((position, velocity));
+            }
+
+            let segment = match self.segments.iter().rev()
+                .find(|s| s.target == body && s.start <= seconds && seconds <= s.end) {
+                Some(s) => s,
+                None => return Err(Error::Ephemeris(format!("No data for {} at {} s TDB", naif_name(body), seconds))),
+            };
+
+            let (p, v) = self.evaluate(segment, seconds)?;
+            for i in 0..3 {
+                position[i] += p[i];
+                velocity[i] += v[i];
+            }
+
+            body = segment.center;
+        }
+
+        Err(Error::Ephemeris(format!("No path from {} to the solar system barycenter", naif_name(body))))
+    }
+
+    /// State of the target relatively to the center of the segment
+    fn evaluate(&self, segment: &Segment, seconds: f64) -> Result<([f64; 3], [f64; 3])>
+    {
+        let components = match segment.data_type {
+            2 => 3,
+            3 => 6,
+            t => return Err(Error::Ephemeris(format!("SPK segments of type {} are not supported", t))),
+        };
+
+        let rotation = match segment.frame {
+            J2000_FRAME => None,
+            ECLIPJ2000_FRAME => Some(Matrix::rot_from_angles(0.0, 0.0, -ECLIPJ2000_OBLIQUITY, RotationAxis::ZYX)),
+            f => return Err(Error::Ephemeris(format!("SPK segments in the frame {} are not supported", f))),
+        };
+
+        if segment.records == 0 || segment.record_size < 2 + components {
+            return Err(Error::Parse(format!("Invalid SPK segment for {}", naif_name(segment.target))));
+        }
+
+        let index = (((seconds - segment.init) / segment.interval_length).floor().max(0.0) as usize)
+            .min(segment.records - 1);
+        let record = segment.address + index * segment.record_size;
+
+        let middle = self.word(record)?;
+        let radius = self.word(record + 1)?;
+        let s = (seconds - middle) / radius;
+
+        // Chebyshev polynomials and their derivatives
+        let count = (segment.record_size - 2) / components;
+        let mut t = vec![1.0, s];
+        let mut dt = vec![0.0, 1.0];
+        for k in 2..count {
+            t.push(2.0 * s * t[k - 1] - t[k - 2]);
+            dt.push(2.0 * t[k - 1] + 2.0 * s * dt[k - 1] - dt[k - 2]);
+        }
+
+        let mut position = [0.0; 3];
+        let mut velocity = [0.0; 3];
+        for i in 0..3 {
+            for k in 0..count {
+                let coefficient = self.word(record + 2 + i * count + k)?;
+                position[i] += coefficient * t[k];
+
+                if components == 3 {
+                    velocity[i] += coefficient * dt[k] / radius;
+                } else {
+                    velocity[i] += self.word(record + 2 + (i + 3) * count + k)? * t[k];
+                }
+            }
+        }
+
+        match rotation {
+            Some(r) => Ok((r.rotate(position), r.rotate(velocity))),
+            None => Ok((position, velocity)),
+        }
+    }
+
+    /// Read a segment from its summary
+    fn segment(&self, start: f64, end: f64, ints: [Result<i32>; 6]) -> Result<Segment>
+    {
+        let [target, center, frame, data_type, address, end_address] = ints;
+        let (address, end_address) = match (usize::try_from(address?), usize::try_from(end_address?)) {
+            (Ok(a), Ok(e)) if a >= 1 && e >= a => (a, e),
+            _ => return Err(Error::Parse(String::from("Invalid addresses of an SPK segment"))),
+        };
+
+        // The directory of the types 2 and 3 is at the end of the segment, after the records
+        let (init, interval_length, record_size, records) = match data_type {
+            Ok(2) | Ok(3) => {
+                let directory = match end_address.checked_sub(3) {
+                    Some(d) if d >= address => d,
+                    _ => return Err(Error::Parse(String::from("SPK segment too short for its directory"))),
+                };
+                let record_size = self.word(directory + 2)?;
+                let records = self.word(directory + 3)?;
+
+                let length = records * record_size;
+                if record_size < 0.0 || records < 0.0 || record_size.fract() != 0.0 || records.fract() != 0.0
+                    || length != (directory - address) as f64 {
+                    return Err(Error::Parse(format!("Invalid directory of an SPK segment: {} records of {} words",
+                                                    records, record_size)));
+                }
+
+                (self.word(directory)?, self.word(directory + 1)?, record_size as usize, records as usize)
+            },
+            _ => (0.0, 0.0, 0, 0),
+        };
+
+        Ok(Segment {
+            target: target?,
+            center: center?,
+            frame: frame?,
+            data_type: data_type?,
+            start,
+            end,
+            address,
+            init,
+            interval_length,
+            record_size,
+            records,
+        })
+    }
+
+    /// Double at the given word address (1-based)
+    fn word(&self, address: usize) -> Result<f64>
+    {
+        match address.checked_sub(1).and_then(|a| a.checked_mul(WORD_LENGTH)) {
+            Some(offset) => self.double_at(offset),
+            None => Err(Error::Parse(format!("Invalid word address {} in the SPK file", address))),
+        }
+    }
+
+    fn double_at(&self, offset: usize) -> Result<f64>
+    {
+        let bytes: [u8; 8] = match offset.checked_add(8).and_then(|end| self.data.get(offset..end)) {
+            Some(b) => b.try_into().unwrap(),
+            None => return Err(Error::Parse(String::from("Truncated SPK file"))),
+        };
+
+        Ok(if self.little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) })
+    }
+
+    fn int_at(&self, offset: usize) -> Result<i32>
+    {
+        let bytes: [u8; 4] = match offset.checked_add(4).and_then(|end| self.data.get(offset..end)) {
+            Some(b) => b.try_into().unwrap(),
+            None => return Err(Error::Parse(String::from("Truncated SPK file"))),
+        };
+
+        Ok(if self.little_endian { i32::from_le_bytes(bytes) } else { i32::from_be_bytes(bytes) })
+    }
+}
+
+/// Use the given kernel for the barycentric frames (ICRS)
+pub fn set_ephemeris(kernel: SpkFile)
+{
+    let mut installed = EPHEMERIS.write().unwrap_or_else(|e| e.into_inner());
+    *installed = Some(kernel);
+}
+
+/// Remove the kernel installed with `set_ephemeris`
+pub fn clear_ephemeris()
+{
+    let mut installed = EPHEMERIS.write().unwrap_or_else(|e| e.into_inner());
+    *installed = None;
+}
+
+/// Position [km] and velocity [km/s] in ICRF of the target relatively to the center, from the
+/// kernel installed with `set_ephemeris`
+pub fn ephemeris_state(target: i32, center: i32, epoch: &Epoch) -> Result<([f64; 3], [f64; 3])>
+{
+    let installed = EPHEMERIS.read().unwrap_or_else(|e| e.into_inner());
+
+    match &*installed {
+        Some(kernel) => kernel.state(target, center, epoch),
+        None => Err(Error::Ephemeris(String::from("No ephemeris installed, see spk::set_ephemeris"))),
+    }
+}
+
+/// # Body of an SPK file
+/// Observed from the Earth with the light-time correction (astrometric position).
+pub struct SpkBody<'a> {
+    kernel: &'a SpkFile,
+    target: i32,
+}
+
+impl<'a> SpkBody<'a> {
+    /// NAIF ID code of the body
+    pub fn id(&self) -> i32
+    {
+        self.target
+    }
+}
+
+impl<'a> Observable for SpkBody<'a> {
+    fn name(&self) -> String {
+        naif_name(self.target)
+    }
+
+    fn observation(&self, observer: &Observer) -> Result<Observation> {
+        self.observation_at(observer, Epoch::now())
+    }
+
+    fn observation_at(&self, observer: &Observer, time: Epoch) -> Result<Observation> {
+        let seconds = tdb_seconds(&time);
+
+        // The body is seen where it was when its light left it
+        let (p_earth, v_earth) = self.kernel.barycentric_state(EARTH, seconds)?;
+        let mut light_time = 0.0;
+        let mut state = ([0.0; 3], [0.0; 3]);
+        for _ in 0..3 {
+            let (p, v) = self.kernel.barycentric_state(self.target, seconds - light_time)?;
+            state = ([0, 1, 2].map(|i| p[i] - p_earth[i]), [0, 1, 2].map(|i| v[i] - v_earth[i]));
+
+            let p = state.0;
+            light_time = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() / SPEED_OF_LIGHT_KM_S;
+        }
+
+        let state = StateVector::new(state.0, state.1, time, Rc::new(GCRF::new(time)))?;
+
+        Observation::new(observer, state)
+    }
+}
@@ -3,14 +3,53 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-use chrono::{DateTime, Utc};
-use sgp4::sgp4::SGP4;
-use crate::geometry::{Angle, Vector};
-use crate::traits::Observable;
+use std::f64::consts::PI;
+use std::rc::Rc;
+use chrono::Duration;
+use crate::constants::SPEED_OF_LIGHT_KM_S;
+use crate::frames::{ecliptic_to_mod, gcrf_to_mod, MOD, TOD};
+use crate::geometry::StateVector;
+use crate::time::{Epoch, JD_J2000, TimeScale};
+use crate::traits::{Framable, Observable};
 use crate::utils::{Observation, Observer};
+use crate::Result;
+
+mod daylight;
+mod vsop87;
+pub use daylight::{sun_times, SunTimes, SUNRISE_ELEVATION};
+pub(crate) use daylight::local_day;
+
+const DAYS_PER_MILLENNIUM: f64 = 365250.0;
+const SECONDS_PER_DAY: f64 = 86400.0;
+const ARCSEC_TO_RAD: f64 = PI / 648000.0;
 
-pub const EARTH_SUN_DISTANCE_KM: u64 = 147_440_000;
 pub const SUN_RADIUS_KM: u64 = 696_340;
+pub const ASTRONOMICAL_UNIT_KM: f64 = 149_597_870.7;
+/// Apparent visual magnitude of the Sun at 1 AU
+pub const SUN_MAGNITUDE_1_AU: f64 = -26.74;
+
+/// # Darkness of the sky, given by the elevation of the Sun below the horizon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Twilight {
+    /// The Sun is 6° below the horizon
+    Civil,
+    /// The Sun is 12° below the horizon
+    Nautical,
+    /// The Sun is 18° below the horizon
+    Astronomical,
+}
+
+impl Twilight {
+    /// Elevation of the center of the Sun [°]
+    pub fn sun_elevation(&self) -> f64
+    {
+        match self {
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
 
 pub struct Sun {
     val: u32
@@ -25,31 +64,103 @@ impl Sun {
     }
 }
 
+/// Geometric geocentric position [km] and velocity [km/s] of the Sun in MOD (mean equator and
+/// equinox of date)
+fn geometric_mod(epoch: &Epoch) -> ([f64; 3], [f64; 3])
+{
+    let tau = (epoch.julian_day(TimeScale::TDB) - JD_J2000) / DAYS_PER_MILLENNIUM;
+    let ([l, b, r], [dl, db, dr]) = vsop87::earth(tau);
+
+    // The Sun is seen from the Earth in the opposite direction, corrected to the FK5 system
+    // (See Meeus, Astronomical Algorithms, 25.9)
+    let t = tau * 10.0;
+    let lambda = l + PI - (1.397 * t + 0.00031 * t * t).to_radians();
+    let lon = l + PI - 0.09033 * ARCSEC_TO_RAD;
+    let lat = -b + 0.03916 * ARCSEC_TO_RAD * (lambda.cos() - lambda.sin());
+    let distance = r * ASTRONOMICAL_UNIT_KM;
+
+    // Rates per second
+    let per_second = 1.0 / (DAYS_PER_MILLENNIUM * SECONDS_PER_DAY);
+    let dlon = dl * per_second;
+    let dlat = -db * per_second;
+    let ddistance = dr * ASTRONOMICAL_UNIT_KM * per_second;
+
+    let (slon, clon) = lon.sin_cos();
+    let (slat, clat) = lat.sin_cos();
+    let position = [distance * clat * clon, distance * clat * slon, distance * slat];
+    let velocity = [
+        ddistance * clat * clon - distance * (slat * clon * dlat + clat * slon * dlon),
+        ddistance * clat * slon - distance * (slat * slon * dlat - clat * clon * dlon),
+        ddistance * slat + distance * clat * dlat,
+    ];
+
+    let to_equator = ecliptic_to_mod(epoch);
+
+    (to_equator.rotate(position), to_equator.rotate(velocity))
+}
+
+/// Geometric geocentric state of the Sun, in MOD
+///
+/// Heliocentric position of the Earth from the VSOP87 theory (about 1" between -2000 and
+/// +6000), with the true distance and velocity.
+pub fn geometric_state(epoch: &Epoch) -> Result<StateVector>
+{
+    let (position, velocity) = geometric_mod(epoch);
+
+    StateVector::new(position, velocity, *epoch, Rc::new(MOD::new(*epoch)))
+}
+
+/// Apparent geocentric state of the Sun, in TOD (true equator and equinox of date)
+///
+/// The Sun is seen where it was when its light left it (light-time), and the motion of the
+/// Earth moves its apparent direction (aberration). Both are given by the geometric state
+/// one light-time earlier, as the Sun is at rest in the heliocentric frame.
+pub fn apparent_state(epoch: &Epoch) -> Result<StateVector>
+{
+    let (position, _) = geometric_mod(epoch);
+    let distance = (position[0] * position[0] + position[1] * position[1] + position[2] * position[2]).sqrt();
+    let light_time = Duration::nanoseconds((distance / SPEED_OF_LIGHT_KM_S * 1e9).round() as i64);
+
+    let (position, velocity) = geometric_mod(&(*epoch - light_time));
+    let mut state = StateVector::new(position, velocity, *epoch, Rc::new(MOD::new(*epoch)))?;
+    state.change_frame(Rc::new(TOD::new(*epoch)))?;
+
+    Ok(state)
+}
+
+/// Geometric geocentric position of the Sun in GCRF [km]
+pub fn position_gcrf(epoch: &Epoch) -> [f64; 3]
+{
+    state_gcrf(epoch).0
+}
+
+/// Geometric geocentric position [km] and velocity [km/s] of the Sun in GCRF
+pub fn state_gcrf(epoch: &Epoch) -> ([f64; 3], [f64; 3])
+{
+    let (position, velocity) = geometric_mod(epoch);
+
+    // Transpose of a rotation is its inverse. The precession is slow enough to neglect its
+    // effect on the velocity.
+    let mod_to_gcrf = gcrf_to_mod(epoch).transpose();
+
+    (mod_to_gcrf.rotate(position), mod_to_gcrf.rotate(velocity))
+}
+
 impl Observable for Sun {
     fn name(&self) -> String {
         String::from("Sun")
     }
 
-    fn observation(&self, observer: &Observer) -> Result<Observation, String> {
-        self.observation_at(observer, Utc::now())
+    fn observation(&self, observer: &Observer) -> Result<Observation> {
+        self.observation_at(observer, Epoch::now())
     }
 
-    fn observation_at(&self, observer: &Observer, time: DateTime<Utc>) -> Result<Observation, String> {
-        // Sun direction (See https://en.wikipedia.org/wiki/Position_of_the_Sun#Ecliptic_coordinates)
-        let jd = SGP4::julian_day(time) - 2451545.0;
-        let l = Angle::from_degrees(280.460 + 0.9856474 * jd); //TODO: Maybe ignore aberration of light
-        let g = Angle::from_degrees(357.528 + 0.9856003 * jd);
-        let ecliptic_lon = Angle::from_degrees(l.degrees() + 1.915 * g.radians().sin() + 0.020 * (g.radians()*2.0).sin());
-
-        //let sun_dir = Vector::from_spherical(ecliptic_lon, Angle::from_degrees(0.0), ref_size as f64 * 2.0);
-        //let sun_dir = Point3::new(sun_dir[0] as f32, sun_dir[2] as f32, sun_dir[1] as f32);
-
-        Ok(Observation {
-            observer: *observer,
-            brightness: 1.0,
-            time,
-            position: Vector::from_spherical(Angle::from_degrees(0.0), ecliptic_lon, EARTH_SUN_DISTANCE_KM as f64).to_point(),
-            speed: Vector::from_cartesian(0.0, 0.0, 0.0),
-        })
+    fn observation_at(&self, observer: &Observer, time: Epoch) -> Result<Observation> {
+        let state = apparent_state(&time)?;
+
+        let mut observation = Observation::new(observer, state)?;
+        observation.magnitude = Some(SUN_MAGNITUDE_1_AU + 5.0 * (observation.look_angles.range / ASTRONOMICAL_UNIT_KM).log10());
+
+        Ok(observation)
     }
 }
\ No newline at end of file
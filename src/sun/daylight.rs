@@ -0,0 +1,86 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Sunrise, sunset, solar transit and twilights
+ *
+ * The apparent elevation of the Sun in the local horizon of the observer is searched over a
+ * local mean solar day, from the local mean midnight, with the pass predictor. The sunrise and
+ * the sunset are when the upper limb of the Sun touches the horizon, taking the refraction
+ * into account; the twilights when the center of the Sun crosses the elevation of the
+ * twilight.
+ */
+
+use chrono::{Duration, NaiveDate};
+use crate::passes::{PassEvent, PassPredictor, RiseSet};
+use crate::time::Epoch;
+use crate::utils::Observer;
+use crate::Result;
+use super::{Sun, Twilight};
+
+/// Elevation of the center of the Sun at sunrise and sunset [°]: 34' of refraction at the
+/// horizon and 16' of semi-diameter
+pub const SUNRISE_ELEVATION: f64 = -0.833;
+
+/// Sampling step of the elevation of the Sun
+const STEP_MINUTES: i64 = 10;
+
+/// # Times of the Sun for an observer during a day
+pub struct SunTimes {
+    /// Highest elevation of the Sun (solar noon), which may be below the horizon
+    pub transit: PassEvent,
+    pub sunrise_sunset: RiseSet,
+    /// Beginning (dawn) and end (dusk) of the civil twilight
+    pub civil: RiseSet,
+    /// Beginning (dawn) and end (dusk) of the nautical twilight
+    pub nautical: RiseSet,
+    /// Beginning (dawn) and end (dusk) of the astronomical twilight
+    pub astronomical: RiseSet,
+}
+
+impl SunTimes {
+    /// Dawn and dusk of the given twilight
+    pub fn twilight(&self, twilight: Twilight) -> RiseSet
+    {
+        match twilight {
+            Twilight::Civil => self.civil,
+            Twilight::Nautical => self.nautical,
+            Twilight::Astronomical => self.astronomical,
+        }
+    }
+}
+
+/// Beginning and end of the local mean solar day of the observer, from midnight at its
+/// longitude
+pub(crate) fn local_day(observer: &Observer, date: NaiveDate) -> (Epoch, Epoch)
+{
+    let midnight = Epoch::from_datetime(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    let offset = Duration::milliseconds((observer.coordinates().longitude() / 15.0 * 3_600_000.0).round() as i64);
+    let start = midnight - offset;
+
+    (start, start + Duration::days(1))
+}
+
+/// Times of the Sun for the observer during the given date. The day is the local mean solar
+/// day, from midnight at the longitude of the observer.
+pub fn sun_times(observer: &Observer, date: NaiveDate) -> Result<SunTimes>
+{
+    let (start, end) = local_day(observer, date);
+
+    let sun = Sun::new();
+    let predictor = |elevation: f64| PassPredictor::new(&sun, observer)
+        .with_min_elevation(elevation)
+        .with_step(Duration::minutes(STEP_MINUTES));
+
+    // Above -90°, the whole day is a single pass culminating at the transit
+    let day = predictor(-90.0).passes(start, end)?;
+
+    Ok(SunTimes {
+        transit: day[0].culmination,
+        sunrise_sunset: predictor(SUNRISE_ELEVATION).rise_set(start, end)?,
+        civil: predictor(Twilight::Civil.sun_elevation()).rise_set(start, end)?,
+        nautical: predictor(Twilight::Nautical.sun_elevation()).rise_set(start, end)?,
+        astronomical: predictor(Twilight::Astronomical.sun_elevation()).rise_set(start, end)?,
+    })
+}
@@ -3,55 +3,260 @@
  * SPDX-License-Identifier: LGPL-2.1-or-later
  */
 
-///
-/// Time
-/// TODO: Move this to a time.rs file
-
-/// Times at which a new leap second has been added
-/// TODO: Is there a better way than "[f64; 27]" ? This table will grow at each new leap second
-const TT_LEAP_SECONDS: [f64; 27] = [
-    2441499.500000,
-    2441683.500000,
-    2442048.500000,
-    2442413.500000,
-    2442778.500000,
-    2443144.500000,
-    2443509.500000,
-    2443874.500000,
-    2444239.500000,
-    2444786.500000,
-    2445151.500000,
-    2445516.500000,
-    2446247.500000,
-    2447161.500000,
-    2447892.500000,
-    2448257.500000,
-    2448804.500000,
-    2449169.500000,
-    2449534.500000,
-    2450083.500000,
-    2450630.500000,
-    2451179.500000,
-    2453736.500000,
-    2454832.500000,
-    2456109.500000,
-    2457204.500000,
-    2457754.500000,
-];
+/* Time scales and epochs
+ *
+ * An `Epoch` is an instant, stored as a two-part Julian date in TAI (whole day + fraction of
+ * day) so that the sub-microsecond resolution is not lost in a single f64. It can be read in
+ * any of the supported time scales.
+ */
+
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Sub};
+use chrono::{DateTime, Duration, Utc};
+use crate::eop::eop_at;
+
+mod leap_seconds;
+pub use leap_seconds::*;
 
 pub const JD_J2000: f64 = 2451545.0;
 
-pub fn get_leap_seconds(jd: f64) -> u32 {
-    let mut i: u32 = 1;
-    while i < TT_LEAP_SECONDS.len() as u32 && jd < TT_LEAP_SECONDS[i as usize] {
-        i += 1;
+/// Julian date of the Unix epoch (1970-01-01T00:00:00 UTC)
+const JD_UNIX_EPOCH: f64 = 2440587.5;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// TT - TAI [s]
+const TT_MINUS_TAI: f64 = 32.184;
+/// TAI - GPS [s]
+const TAI_MINUS_GPS: f64 = 19.0;
+
+/// UT1 - UTC [s] at the given UTC Julian date (0 if no EOP table is loaded)
+fn ut1_minus_utc(jd_utc: f64) -> f64 {
+    eop_at(jd_utc).ut1_utc
+}
+
+/// TDB - TT [s] at the given TT Julian date
+///
+/// Main periodic terms, good to about 30 microseconds
+/// (See the Explanatory Supplement to the Astronomical Almanac)
+fn tdb_minus_tt(jd_tt: f64) -> f64 {
+    let g = (357.53 + 0.98560028 * (jd_tt - JD_J2000)).to_radians();
+    0.001657 * g.sin() + 0.000014 * (2.0 * g).sin()
+}
+
+/// # Time scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeScale {
+    /// Coordinated Universal Time
+    UTC,
+    /// International Atomic Time
+    TAI,
+    /// Terrestrial Time
+    TT,
+    /// Universal Time, following the rotation of the Earth
+    UT1,
+    /// GPS time
+    GPS,
+    /// Barycentric Dynamical Time
+    TDB,
+}
+
+impl fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TimeScale::UTC => "UTC",
+            TimeScale::TAI => "TAI",
+            TimeScale::TT => "TT",
+            TimeScale::UT1 => "UT1",
+            TimeScale::GPS => "GPS",
+            TimeScale::TDB => "TDB",
+        };
+
+        write!(f, "{}", name)
     }
+}
 
-    i + 10
+/// # Epoch
+/// An instant in time, independent of the time scale it is expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Epoch {
+    day: f64,       // TAI Julian date at 0h (ends with .5)
+    fraction: f64,  // Fraction of day, in [0, 1)
 }
 
-//TODO: get a better understanding of this
-pub fn jd_utc_to_tt(jd: f64) -> f64 {
-    let ls = get_leap_seconds(jd) as f64;
-    jd + (ls + 32.184) / 86400.0
-}
\ No newline at end of file
+impl Epoch {
+    pub fn now() -> Epoch
+    {
+        Epoch::from_datetime(Utc::now())
+    }
+
+    /// Create an epoch from a UTC date and time
+    pub fn from_datetime(date_time: DateTime<Utc>) -> Epoch
+    {
+        let seconds = date_time.timestamp();
+        let day = JD_UNIX_EPOCH + seconds.div_euclid(86400) as f64;
+        let fraction = (seconds.rem_euclid(86400) as f64 + date_time.timestamp_subsec_nanos() as f64 * 1e-9) / SECONDS_PER_DAY;
+
+        Epoch::from_julian_day_parts(day, fraction, TimeScale::UTC)
+    }
+
+    /// Create an epoch from a Julian date in the given time scale
+    pub fn from_julian_day(jd: f64, scale: TimeScale) -> Epoch
+    {
+        Epoch::from_julian_day_parts(jd, 0.0, scale)
+    }
+
+    /// Create an epoch from a Julian date split in two parts (`day + fraction`) in the given
+    /// time scale. Any split is accepted, the precision is best when `day` is a whole day.
+    pub fn from_julian_day_parts(day: f64, fraction: f64, scale: TimeScale) -> Epoch
+    {
+        let (day, fraction) = normalize(day, fraction);
+
+        let offset = match scale {
+            TimeScale::TAI => 0.0,
+            TimeScale::TT => -TT_MINUS_TAI,
+            TimeScale::GPS => TAI_MINUS_GPS,
+            TimeScale::UTC => tai_minus_utc(day + fraction),
+            TimeScale::UT1 => {
+                // UT1 - UTC is small enough to look up the tables with the UT1 date
+                let jd_ut1 = day + fraction;
+                tai_minus_utc(jd_ut1) - ut1_minus_utc(jd_ut1)
+            },
+            TimeScale::TDB => {
+                // TDB - TT is periodic and small: evaluating it at TDB instead of TT is
+                // negligible
+                -tdb_minus_tt(day + fraction) - TT_MINUS_TAI
+            },
+        };
+
+        Epoch::from_normalized(day, fraction + offset / SECONDS_PER_DAY)
+    }
+
+    /// Julian date in the given time scale
+    pub fn julian_day(&self, scale: TimeScale) -> f64
+    {
+        let (day, fraction) = self.julian_day_parts(scale);
+        day + fraction
+    }
+
+    /// Julian date in the given time scale, as a whole day (at 0h) and a fraction of day
+    pub fn julian_day_parts(&self, scale: TimeScale) -> (f64, f64)
+    {
+        let offset = match scale {
+            TimeScale::TAI => 0.0,
+            TimeScale::TT => TT_MINUS_TAI,
+            TimeScale::GPS => -TAI_MINUS_GPS,
+            TimeScale::UTC => -self.tai_minus_utc(),
+            TimeScale::UT1 => {
+                let utc = self.julian_day(TimeScale::UTC);
+                -self.tai_minus_utc() + ut1_minus_utc(utc)
+            },
+            TimeScale::TDB => {
+                let tt = self.day + self.fraction + TT_MINUS_TAI / SECONDS_PER_DAY;
+                TT_MINUS_TAI + tdb_minus_tt(tt)
+            },
+        };
+
+        normalize(self.day, self.fraction + offset / SECONDS_PER_DAY)
+    }
+
+    /// Julian centuries since J2000 in the given time scale
+    pub fn julian_centuries(&self, scale: TimeScale) -> f64
+    {
+        let (day, fraction) = self.julian_day_parts(scale);
+        ((day - JD_J2000) + fraction) / 36525.0
+    }
+
+    /// Greenwich Mean Sidereal Time [rad] (IAU 1982 model)
+    pub fn gmst(&self) -> f64
+    {
+        let t = self.julian_centuries(TimeScale::UT1);
+
+        // [s]
+        let gmst = 67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t + 0.093104 * t * t - 6.2e-6 * t * t * t;
+
+        (gmst % SECONDS_PER_DAY / SECONDS_PER_DAY * 2.0 * PI).rem_euclid(2.0 * PI)
+    }
+
+    /// UTC date and time of this epoch
+    pub fn to_datetime(&self) -> DateTime<Utc>
+    {
+        let (day, fraction) = self.julian_day_parts(TimeScale::UTC);
+
+        let days = (day - JD_UNIX_EPOCH).round() as i64;
+        let nanos = (fraction * SECONDS_PER_DAY * 1e9).round() as i64;
+
+        DateTime::UNIX_EPOCH + Duration::days(days) + Duration::nanoseconds(nanos)
+    }
+
+    fn tai_minus_utc(&self) -> f64
+    {
+        // The table is indexed by UTC dates, which are a few seconds before TAI
+        let tai = self.day + self.fraction;
+        let guess = tai - tai_minus_utc(tai) / SECONDS_PER_DAY;
+        tai_minus_utc(guess)
+    }
+
+    fn from_normalized(day: f64, fraction: f64) -> Epoch
+    {
+        let (day, fraction) = normalize(day, fraction);
+
+        Epoch {
+            day,
+            fraction
+        }
+    }
+}
+
+/// Split a Julian date so that `day` ends with .5 (0h) and `fraction` is in [0, 1)
+fn normalize(day: f64, fraction: f64) -> (f64, f64)
+{
+    let midnight = (day - 0.5).floor() + 0.5;
+    let fraction = fraction + (day - midnight);
+    let whole = fraction.floor();
+
+    (midnight + whole, fraction - whole)
+}
+
+impl From<DateTime<Utc>> for Epoch {
+    fn from(date_time: DateTime<Utc>) -> Self {
+        Epoch::from_datetime(date_time)
+    }
+}
+
+impl Add<Duration> for Epoch {
+    type Output = Epoch;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        let seconds = rhs.num_seconds() as f64;
+        let nanos = rhs.subsec_nanos() as f64 * 1e-9;
+
+        Epoch::from_normalized(self.day, self.fraction + (seconds + nanos) / SECONDS_PER_DAY)
+    }
+}
+
+impl Sub<Duration> for Epoch {
+    type Output = Epoch;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl Sub<Epoch> for Epoch {
+    type Output = Duration;
+
+    /// Elapsed time between two epochs (in SI seconds)
+    fn sub(self, rhs: Epoch) -> Self::Output {
+        let days = self.day - rhs.day;
+        let seconds = (self.fraction - rhs.fraction) * SECONDS_PER_DAY;
+
+        Duration::days(days.round() as i64) + Duration::nanoseconds((seconds * 1e9).round() as i64)
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_datetime())
+    }
+}
@@ -0,0 +1,325 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Leap seconds
+ *
+ * TAI - UTC is read from a table which can be loaded from the IERS files:
+ *  - Leap_Second.dat (https://hpiers.obspm.fr/iers/bul/bulc/Leap_Second.dat)
+ *  - tai-utc.dat (https://maia.usno.navy.mil/ser7/tai-utc.dat), which also contains the drift
+ *    of UTC before 1972
+ *
+ * When no table is loaded, a built-in copy is used.
+ */
+
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use chrono::NaiveDate;
+use crate::time::{Epoch, TimeScale};
+use crate::{Error, Result};
+
+const MJD_OFFSET: f64 = 2400000.5;
+
+/// Interval of the table: from `jd` (UTC), TAI - UTC = offset + (MJD - mjd_ref) * rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Interval {
+    jd: f64,
+    offset: f64,
+    mjd_ref: f64,
+    rate: f64,
+}
+
+impl Interval {
+    const fn new(jd: f64, offset: f64) -> Interval {
+        Interval {
+            jd,
+            offset,
+            mjd_ref: 0.0,
+            rate: 0.0,
+        }
+    }
+
+    const fn drifting(jd: f64, offset: f64, mjd_ref: f64, rate: f64) -> Interval {
+        Interval {
+            jd,
+            offset,
+            mjd_ref,
+            rate,
+        }
+    }
+
+    fn tai_minus_utc(&self, jd_utc: f64) -> f64 {
+        self.offset + (jd_utc - MJD_OFFSET - self.mjd_ref) * self.rate
+    }
+}
+
+/// Built-in table, from tai-utc.dat
+const BUILTIN_INTERVALS: [Interval; 41] = [
+    Interval::drifting(2437300.5, 1.4228180, 37300.0, 0.001296),
+    Interval::drifting(2437512.5, 1.3728180, 37300.0, 0.001296),
+    Interval::drifting(2437665.5, 1.8458580, 37665.0, 0.0011232),
+    Interval::drifting(2438334.5, 1.9458580, 37665.0, 0.0011232),
+    Interval::drifting(2438395.5, 3.2401300, 38761.0, 0.001296),
+    Interval::drifting(2438486.5, 3.3401300, 38761.0, 0.001296),
+    Interval::drifting(2438639.5, 3.4401300, 38761.0, 0.001296),
+    Interval::drifting(2438761.5, 3.5401300, 38761.0, 0.001296),
+    Interval::drifting(2438820.5, 3.6401300, 38761.0, 0.001296),
+    Interval::drifting(2438942.5, 3.7401300, 38761.0, 0.001296),
+    Interval::drifting(2439004.5, 3.8401300, 38761.0, 0.001296),
+    Interval::drifting(2439126.5, 4.3131700, 39126.0, 0.002592),
+    Interval::drifting(2439887.5, 4.2131700, 39126.0, 0.002592),
+    Interval::new(2441317.5, 10.0),
+    Interval::new(2441499.5, 11.0),
+    Interval::new(2441683.5, 12.0),
+    Interval::new(2442048.5, 13.0),
+    Interval::new(2442413.5, 14.0),
+    Interval::new(2442778.5, 15.0),
+    Interval::new(2443144.5, 16.0),
+    Interval::new(2443509.5, 17.0),
+    Interval::new(2443874.5, 18.0),
+    Interval::new(2444239.5, 19.0),
+    Interval::new(2444786.5, 20.0),
+    Interval::new(2445151.5, 21.0),
+    Interval::new(2445516.5, 22.0),
+    Interval::new(2446247.5, 23.0),
+    Interval::new(2447161.5, 24.0),
+    Interval::new(2447892.5, 25.0),
+    Interval::new(2448257.5, 26.0),
+    Interval::new(2448804.5, 27.0),
+    Interval::new(2449169.5, 28.0),
+    Interval::new(2449534.5, 29.0),
+    Interval::new(2450083.5, 30.0),
+    Interval::new(2450630.5, 31.0),
+    Interval::new(2451179.5, 32.0),
+    Interval::new(2453736.5, 33.0),
+    Interval::new(2454832.5, 34.0),
+    Interval::new(2456109.5, 35.0),
+    Interval::new(2457204.5, 36.0),
+    Interval::new(2457754.5, 37.0),
+];
+
+/// Expiration of the built-in table (2027-06-28, IERS Bulletin C 72)
+const BUILTIN_EXPIRES: f64 = 2461584.5;
+
+/// Table installed with `set_leap_seconds`
+static LEAP_SECONDS: RwLock<Option<LeapSeconds>> = RwLock::new(None);
+
+/// # Table of the leap seconds (TAI - UTC)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeapSeconds {
+    intervals: Vec<Interval>,
+    expires: Option<f64>,
+}
+
+impl LeapSeconds {
+    /// Table shipped with tardis
+    pub fn builtin() -> LeapSeconds
+    {
+        LeapSeconds {
+            intervals: BUILTIN_INTERVALS.to_vec(),
+            expires: Some(BUILTIN_EXPIRES),
+        }
+    }
+
+    /// Load an IERS Leap_Second.dat or tai-utc.dat file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<LeapSeconds>
+    {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return Err(Error::Io(e)),
+        };
+
+        LeapSeconds::parse(&content)
+    }
+
+    /// Parse the content of an IERS Leap_Second.dat or tai-utc.dat file
+    pub fn parse(content: &str) -> Result<LeapSeconds>
+    {
+        let table = if content.contains("=JD") {
+            LeapSeconds::parse_tai_utc(content)?
+        } else {
+            LeapSeconds::parse_leap_second_dat(content)?
+        };
+
+        if table.intervals.is_empty() {
+            return Err(Error::Parse(String::from("The leap second table is empty")));
+        }
+
+        if table.intervals.windows(2).any(|w| w[0].jd >= w[1].jd) {
+            return Err(Error::Parse(String::from("The leap second table is not sorted by date")));
+        }
+
+        Ok(table)
+    }
+
+    /// TAI - UTC [s] at the given UTC Julian date.
+    ///
+    /// Before the first entry of the table, the first value is used. After the expiration date,
+    /// the last value is used (see `is_expired`).
+    pub fn tai_minus_utc(&self, jd_utc: f64) -> f64
+    {
+        lookup(&self.intervals, jd_utc)
+    }
+
+    /// UTC Julian date after which the table may be missing leap seconds, if known
+    pub fn expires(&self) -> Option<f64>
+    {
+        self.expires
+    }
+
+    /// Return true if a leap second could have been announced after this table was published,
+    /// for the given epoch
+    pub fn is_expired(&self, epoch: &Epoch) -> bool
+    {
+        match self.expires {
+            Some(expires) => epoch.julian_day(TimeScale::UTC) >= expires,
+            None => false,
+        }
+    }
+
+    /// Leap_Second.dat:
+    /// `#  File expires on 28 June 2027`
+    /// `    41317.0    1  1 1972       10`
+    fn parse_leap_second_dat(content: &str) -> Result<LeapSeconds>
+    {
+        let mut intervals = Vec::new();
+        let mut expires = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(date) = comment.trim().strip_prefix("File expires on") {
+                    expires = Some(parse_expiration(date.trim())?);
+                }
+                continue;
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 5 {
+                return Err(Error::Parse(format!("Invalid leap second line: {}", line)));
+            }
+
+            let mjd = parse_number(fields[0], line)?;
+            let offset = parse_number(fields[4], line)?;
+
+            intervals.push(Interval::new(mjd + MJD_OFFSET, offset));
+        }
+
+        Ok(LeapSeconds {
+            intervals,
+            expires,
+        })
+    }
+
+    /// tai-utc.dat:
+    /// ` 1962 JAN  1 =JD 2437665.5  TAI-UTC=   1.8458580 S + (MJD - 37665.) X 0.0011232S`
+    ///
+    /// The unit of the rate is not always separated from the value.
+    fn parse_tai_utc(content: &str) -> Result<LeapSeconds>
+    {
+        let mut intervals = Vec::new();
+
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let jd = parse_number(field_after(line, "=JD")?, line)?;
+            let offset = parse_number(field_after(line, "TAI-UTC=")?, line)?;
+            let mjd_ref = parse_number(field_after(line, "(MJD -")?.trim_end_matches(')'), line)?;
+            let rate = parse_number(field_after(line, " X ")?.trim_end_matches('S'), line)?;
+
+            intervals.push(Interval::drifting(jd, offset, mjd_ref, rate));
+        }
+
+        // tai-utc.dat does not tell when it expires
+        Ok(LeapSeconds {
+            intervals,
+            expires: None,
+        })
+    }
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        LeapSeconds::builtin()
+    }
+}
+
+/// Use the given table for all the time scale conversions
+pub fn set_leap_seconds(table: LeapSeconds)
+{
+    let mut installed = LEAP_SECONDS.write().unwrap_or_else(|e| e.into_inner());
+    *installed = Some(table);
+}
+
+/// Table currently used for the time scale conversions
+pub fn leap_seconds() -> LeapSeconds
+{
+    let installed = LEAP_SECONDS.read().unwrap_or_else(|e| e.into_inner());
+
+    match &*installed {
+        Some(table) => table.clone(),
+        None => LeapSeconds::builtin(),
+    }
+}
+
+/// TAI - UTC [s] at the given UTC Julian date, with the table currently used
+pub fn tai_minus_utc(jd_utc: f64) -> f64
+{
+    let installed = LEAP_SECONDS.read().unwrap_or_else(|e| e.into_inner());
+
+    match &*installed {
+        Some(table) => table.tai_minus_utc(jd_utc),
+        None => lookup(&BUILTIN_INTERVALS, jd_utc),
+    }
+}
+
+fn lookup(intervals: &[Interval], jd_utc: f64) -> f64
+{
+    // Index of the first interval starting after the date
+    let next = intervals.partition_point(|i| i.jd <= jd_utc);
+    let interval = &intervals[next.saturating_sub(1)];
+
+    interval.tai_minus_utc(jd_utc)
+}
+
+fn field_after<'a>(line: &'a str, key: &str) -> Result<&'a str>
+{
+    let rest = match line.find(key) {
+        Some(i) => &line[i + key.len()..],
+        None => return Err(Error::Parse(format!("Missing '{}' in leap second line: {}", key, line))),
+    };
+
+    match rest.split_whitespace().next() {
+        Some(f) => Ok(f),
+        None => Err(Error::Parse(format!("Missing value after '{}' in leap second line: {}", key, line))),
+    }
+}
+
+fn parse_number(field: &str, line: &str) -> Result<f64>
+{
+    match field.parse::<f64>() {
+        Ok(v) => Ok(v),
+        Err(_) => Err(Error::Parse(format!("Invalid number '{}' in leap second line: {}", field, line))),
+    }
+}
+
+/// Parse a date like "28 December 2025" into a UTC Julian date
+fn parse_expiration(date: &str) -> Result<f64>
+{
+    let date = match NaiveDate::parse_from_str(date, "%d %B %Y") {
+        Ok(d) => d,
+        Err(_) => return Err(Error::Parse(format!("Invalid expiration date: {}", date))),
+    };
+
+    let unix_epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    Ok(super::JD_UNIX_EPOCH + (date - unix_epoch).num_days() as f64)
+}
@@ -11,10 +11,13 @@ use std::rc::Rc;
 use chrono::{Utc, DateTime, Duration, NaiveDate, NaiveTime};
 use sgp4::sgp4::{ConstantsSet, OpsMode, SGP4};
 
-use crate::geometry::{Angle, Point, Vector};
+use crate::geometry::{Angle, StateVector};
 use crate::frames::{TEME, GCRF};
+use crate::magnitude::{intrinsic_magnitude, satellite_magnitude};
+use crate::time::Epoch;
 use crate::utils::{Coordinates, Observation, Observer};
-use crate::traits::{Framable, Observable, Frame};
+use crate::traits::{Observable, Frame};
+use crate::{Error, Result};
 
 pub enum SatelliteClass {
     Unclassified,
@@ -49,6 +52,8 @@ pub struct TLE {
     pub mean_anomaly: Angle,
     pub mean_motion: f64,
     pub revolutions: u32,
+    /// Standard magnitude (See `magnitude`). When None, the magnitude table is used.
+    pub intrinsic_magnitude: Option<f64>,
 }
 
 impl fmt::Display for TLE {
@@ -87,7 +92,7 @@ impl fmt::Display for TLE {
 }
 
 impl TLE {
-    fn checksum(line: &[u8]) -> Result<(), String>
+    fn checksum(line: &[u8]) -> Result<()>
     {
         let checksum = match TLE::parse_number(&line[68..69]) {
             Ok(n) => n,
@@ -114,14 +119,18 @@ impl TLE {
         count -= checksum;
 
         if count % 10 != checksum {
-            return Err(String::from("Invalid checksum"));
+            return Err(Error::Parse(String::from("Invalid checksum")));
         }
 
         Ok(())
     }
 
-    pub fn from_lines(line1: &[u8], line2: &[u8], name_line: &[u8]) -> Result<TLE, String>
+    pub fn from_lines(line1: &[u8], line2: &[u8], name_line: &[u8]) -> Result<TLE>
     {
+        if line1.len() < 69 || line2.len() < 69 {
+            return Err(Error::Parse(String::from("TLE lines must have 69 characters")));
+        }
+
         match TLE::checksum(line1) {
             Ok(_) => {}
             Err(e) => return Err(e),
@@ -139,12 +148,12 @@ impl TLE {
         };
 
         /* Check line numbers */
-        if TLE::parse_number(&line1[0..1]).unwrap() != 1 {
-            return Err("Line 1 number is incorrect".to_string());
+        if TLE::parse_number(&line1[0..1])? != 1 {
+            return Err(Error::Parse("Line 1 number is incorrect".to_string()));
         }
 
-        if TLE::parse_number(&line2[0..1]).unwrap() != 2 {
-            return Err("Line 2 number is incorrect".to_string());
+        if TLE::parse_number(&line2[0..1])? != 2 {
+            return Err(Error::Parse("Line 2 number is incorrect".to_string()));
         }
 
         /* Get satellite number */
@@ -245,33 +254,34 @@ impl TLE {
             mean_anomaly,
             mean_motion,
             revolutions,
+            intrinsic_magnitude: None,
         })
     }
 
-    fn parse_string(bytes: &[u8]) -> Result<String, String>
+    fn parse_string(bytes: &[u8]) -> Result<String>
     {
         let name = match String::from_utf8(Vec::from(bytes)) {
             Ok(n) => n,
-            Err(e) => return Err(String::from("Cannot parse String ") + &e.to_string())
+            Err(e) => return Err(Error::Parse(String::from("Cannot parse String ") + &e.to_string()))
         };
 
         Ok(name.trim_end().to_string())
     }
 
-    fn parse_number(bytes: &[u8]) -> Result<i32, String>
+    fn parse_number(bytes: &[u8]) -> Result<i32>
     {
         let number = match String::from_utf8(Vec::from(bytes)) {
             Ok(n) => n.trim_start().to_string(),
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(Error::Parse(e.to_string())),
         };
 
         match number.parse() {
             Ok(n) => Ok(n),
-            Err(e) => Err(e.to_string())
+            Err(e) => Err(Error::Parse(e.to_string()))
         }
     }
 
-    fn parse_number_i(bytes: &[u8]) -> Result<f64, String>
+    fn parse_number_i(bytes: &[u8]) -> Result<f64>
     {
         let mut number;
 
@@ -287,29 +297,29 @@ impl TLE {
                     |c: char| c.is_whitespace() || c == '-' || c == '.'
                 ))
             },
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(Error::Parse(e.to_string())),
         };
 
         match number.parse() {
             Ok(n) => Ok(n),
-            Err(e) => Err(e.to_string())
+            Err(e) => Err(Error::Parse(e.to_string()))
         }
     }
 
-    fn parse_float(bytes: &[u8]) -> Result<f64, String>
+    fn parse_float(bytes: &[u8]) -> Result<f64>
     {
         let number = match String::from_utf8(Vec::from(bytes)) {
             Ok(n) => n.trim_start().to_string(),
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(Error::Parse(e.to_string())),
         };
 
         match number.parse() {
             Ok(n) => Ok(n),
-            Err(e) => Err(e.to_string())
+            Err(e) => Err(Error::Parse(e.to_string()))
         }
     }
 
-    fn parse_pow_10(bytes: &[u8]) -> Result<f64, String>
+    fn parse_pow_10(bytes: &[u8]) -> Result<f64>
     {
         let base = match TLE::parse_number_i(&bytes[0..bytes.len() - 2]) {
             Ok(n) => n as f64,
@@ -324,17 +334,17 @@ impl TLE {
         Ok(base * 10_f64.powi(exp))
     }
 
-    fn string_to_class(class: &str) -> Result<SatelliteClass, String>
+    fn string_to_class(class: &str) -> Result<SatelliteClass>
     {
         match class {
             "U" => Ok(SatelliteClass::Unclassified),
             "C" => Ok(SatelliteClass::Classified),
             "S" => Ok(SatelliteClass::Secret),
-            _ => Err(String::from("Invalid class string ") + class)
+            _ => Err(Error::Parse(String::from("Invalid class string ") + class))
         }
     }
 
-    fn parse_designator(designator: &[u8]) -> Result<Designator, String>
+    fn parse_designator(designator: &[u8]) -> Result<Designator>
     {
         let launch_year = match TLE::parse_number(&designator[0..=1]) {
             Ok(n) => n as u8,
@@ -358,7 +368,7 @@ impl TLE {
         })
     }
 
-    fn parse_date(date: &[u8]) -> Result<DateTime<Utc>, String>
+    fn parse_date(date: &[u8]) -> Result<DateTime<Utc>>
     {
         let year = match TLE::parse_number(&date[0..=1]) {
             Ok(n) => n,
@@ -390,7 +400,7 @@ impl TLE {
         let date = NaiveDate::from_ymd(year, 1, 1);
         let date = match date.checked_add_signed(Duration::days(days as i64 - 1)) {
             Some(d) => d,
-            None => return Err(String::from("Date is out of bounds")),
+            None => return Err(Error::Parse(String::from("Date is out of bounds"))),
         };
 
         let time = NaiveTime::from_num_seconds_from_midnight(time_of_day.floor() as u32, 0);
@@ -405,12 +415,12 @@ impl Observable for TLE {
         self.name.clone()
     }
 
-    fn observation(&self, obs: &Observer) -> Result<Observation, String>
+    fn observation(&self, obs: &Observer) -> Result<Observation>
     {
-        self.observation_at(obs, Utc::now())
+        self.observation_at(obs, Epoch::now())
     }
 
-    fn observation_at(&self, obs: &Observer, time: DateTime<Utc>) -> Result<Observation, String>
+    fn observation_at(&self, obs: &Observer, time: Epoch) -> Result<Observation>
     {
         //let I = Vector::from_tuple([1f64, 0f64, 0f64]);
         //let J = Vector::from_tuple([0f64, 1f64, 0f64]);
@@ -442,39 +452,25 @@ impl Observable for TLE {
             self.right_ascension.radians()
         ) {
             Ok(s) => s,
-            Err(e) => {
-                println!("Error on satellite {} ({}): {}", self.number, self.name(), e);
-                return Err(String::from("Cannot compute satellite position"));
-            }
+            Err(e) => return Err(Error::Propagation(e)),
         };
 
-        let res = match sgp4.compute(time) {
+        let res = match sgp4.compute(time.to_datetime()) {
             Ok(r) => r,
-            Err(e) => {
-                println!("Error on satellite {} ({}): {}", self.number, self.name(), e);
-                return Err(String::from("Cannot compute satellite position"));
-            }
+            Err(e) => return Err(Error::Propagation(e)),
         };
 
         //println!("[{}] Satellite {} is at {} km moving at {} km/s", res.time(), self.name(), res.altitude(), res.velocity());
         //println!("{}", Angle::from_vectors(&I, &J));
 
-        /// Compute azimuth angle //TODO: move it to a Frame conversion
-        //1. Project res.position_vect() on observer plane.
         let teme_frame: Rc<dyn Frame> = Rc::new(TEME::new(time));
+        let state = StateVector::new(res.position_vect(), res.velocity_vect(), time, teme_frame)?;
+        let mut observation = Observation::new(obs, state)?;
 
-        let mut pos_point = Point::from_tuple(res.position_vect());
-        pos_point.set_frame(Rc::clone(&teme_frame));
-
-        let mut speed_vect = Vector::from_tuple(res.velocity_vect());
-        speed_vect.set_frame(Rc::clone(&teme_frame));
+        if let Some(intrinsic) = self.intrinsic_magnitude.or_else(|| intrinsic_magnitude(self.number)) {
+            observation.magnitude = satellite_magnitude(intrinsic, &observation)?;
+        }
 
-        Ok(Observation {
-            time,
-            observer: *obs,
-            position: pos_point,
-            speed: speed_vect,
-            brightness: 0f64
-        })
+        Ok(observation)
     }
 }
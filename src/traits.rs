@@ -4,19 +4,19 @@
  */
 
 use std::rc::Rc;
-use chrono::{
-    DateTime,
-    Utc
-};
 use crate::utils::{
     Coordinates,
     Observer,
     Observation
 };
+use crate::frames::Transform;
+use crate::time::Epoch;
+use crate::Result;
 
 pub trait Framable {
     // This function will convert the coordinates of this element into the new_frame
-    fn change_frame(&mut self, new_frame: Rc<dyn Frame>);
+    // It fails when there is no path between the frames in the transformation graph
+    fn change_frame(&mut self, new_frame: Rc<dyn Frame>) -> Result<()>;
 
     // This function replaces the Frame of the element without conversion
     fn set_frame(&mut self, frame: Rc<dyn Frame>);
@@ -26,24 +26,22 @@ pub trait Frame {
     fn name(&self) -> String;
     //fn date_time(&self) -> DateTime<Utc>;
 
+    /// Epoch of the frame, None if its orientation and origin do not depend on time.
     ///
-    /// Return true if the frame has a different configuration depending on time
-    ///
-    /// For a better Speed support, all Frames should be timed.
-    //fn has_obs_time(&self) -> bool;
+    /// The transforms between frames are declared in the transformation graph of the `frames`
+    /// module, by frame name (See `frames::register_transform`).
+    fn epoch(&self) -> Option<Epoch>;
 
-    // FIXME: Intermediate GCRF is good but might lack precision for objects that are far from earth.
-    //  Using ICRS should be better for objects that are far from earth(other planets, other stars,...).
-    //  But GCRF is better for near-earth objects.
-    //  In a first time, let's focus on earth satellites and use GCRF.
-    //  Later, a solution could be based on the elegant Astropy's TransformGraph system
-    //  See https://github.com/astropy/astropy/blob/77208dd7d7265df382849de841c890b3af996323/astropy/coordinates/transformations.py#L76
-    fn to_gcrf(&self, point: [f64; 3]) -> [f64; 3];
-    fn from_gcrf(&self, point: [f64; 3]) -> [f64; 3];
+    /// Name of the frame of the transformation graph this frame is attached to, and the
+    /// transform from it. Only used by the frames which are not in the graph because they
+    /// depend on other parameters than the epoch, like the topocentric frames.
+    fn transform_from_parent(&self) -> Option<(String, Transform)> {
+        None
+    }
 }
 
 pub trait Observable {
     fn name(&self) -> String;
-    fn observation(&self, observer: &Observer) -> Result<Observation, String>;
-    fn observation_at(&self, observer: &Observer, time: DateTime<Utc>) -> Result<Observation, String>;
+    fn observation(&self, observer: &Observer) -> Result<Observation>;
+    fn observation_at(&self, observer: &Observer, time: Epoch) -> Result<Observation>;
 }
\ No newline at end of file
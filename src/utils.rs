@@ -5,40 +5,94 @@
 
 use std::fmt;
 use std::rc::Rc;
-use chrono::{DateTime, Utc};
 use sgp4::sgp4::SGP4Result;
-use crate::constants::EARTH_EQUATORIAL_RADIUS_KM;
-use crate::geometry::{Angle, Point, Vector};
+use crate::ellipsoid::Ellipsoid;
+use crate::geometry::{Angle, StateVector, Vector};
 use crate::frames;
-use crate::frames::{ECEF, GCRF};
+use crate::frames::{ITRF, SEZ};
+use crate::time::Epoch;
 use crate::traits::Framable;
+use crate::{Error, Result};
 
+/// # Geodetic coordinates on a reference ellipsoid
 #[derive(Copy, Clone)]
 pub struct Coordinates {
-    lat: f64,
-    lon: f64,
+    lat: f64,               // Geodetic latitude [°]
+    lon: f64,               // Longitude [°]
+    alt: f64,               // Altitude above the ellipsoid [km]
+    ellipsoid: Ellipsoid,
 }
 
 impl Coordinates {
+    /// Point at the surface of the WGS84 ellipsoid
     pub fn new(lat: f64, lon: f64) -> Coordinates
+    {
+        Coordinates::with_altitude(lat, lon, 0.0)
+    }
+
+    /// Point at the given altitude [km] above the WGS84 ellipsoid
+    pub fn with_altitude(lat: f64, lon: f64, alt: f64) -> Coordinates
+    {
+        Coordinates::on_ellipsoid(lat, lon, alt, Ellipsoid::WGS84)
+    }
+
+    pub fn on_ellipsoid(lat: f64, lon: f64, alt: f64, ellipsoid: Ellipsoid) -> Coordinates
     {
         Coordinates {
             lat,
-            lon
+            lon,
+            alt,
+            ellipsoid
         }
     }
 
+    /// Geodetic coordinates of an Earth-fixed (ITRF) position [km], e.g. the sub-satellite point
+    pub fn from_cartesian(position: [f64; 3], ellipsoid: Ellipsoid) -> Coordinates
+    {
+        let (lat, lon, alt) = ellipsoid.to_geodetic(position);
+
+        Coordinates::on_ellipsoid(lat.to_degrees(), lon.to_degrees(), alt, ellipsoid)
+    }
+
+    /// Geodetic latitude [°]
+    pub fn latitude(&self) -> f64
+    {
+        self.lat
+    }
+
+    /// Longitude [°], positive to the East
+    pub fn longitude(&self) -> f64
+    {
+        self.lon
+    }
+
+    /// Altitude above the ellipsoid [km]
+    pub fn altitude(&self) -> f64
+    {
+        self.alt
+    }
+
+    pub fn ellipsoid(&self) -> Ellipsoid
+    {
+        self.ellipsoid
+    }
+
+    /// Earth-fixed (ITRF) cartesian coordinates [km]
+    pub fn to_cartesian(&self) -> [f64; 3]
+    {
+        let lat = Angle::from_degrees(self.lat).radians();
+        let lon = Angle::from_degrees(self.lon).radians();
+
+        self.ellipsoid.to_cartesian(lat, lon, self.alt)
+    }
+
     /// Return a the position of the observer as a vector.
     /// TODO: This should actually be changed to a Frame.
     pub fn to_vector(&self) -> Vector
     {
-        let mut v = Vector::from_spherical(
-            Angle::from_degrees(self.lon),
-            Angle::from_degrees(self.lat),
-            EARTH_EQUATORIAL_RADIUS_KM
-        );
+        let mut v = Vector::from_tuple(self.to_cartesian());
 
-        v.set_frame(Rc::new(ECEF::new(Utc::now())));
+        v.set_frame(Rc::new(ITRF::new(Epoch::now())));
 
         v
     }
@@ -46,7 +100,7 @@ impl Coordinates {
 
 impl fmt::Display for Coordinates {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Coordinates: [lat: {}, lon: {}]", self.lat, self.lon)
+        write!(f, "Coordinates: [lat: {}, lon: {}, alt: {} km]", self.lat, self.lon, self.alt)
     }
 }
 
@@ -77,7 +131,7 @@ impl Observer {
         self.coordinates
     }
 
-    /*pub fn plane(&self) -> Result<Plane, String>
+    /*pub fn plane(&self) -> Result<Plane>
     {
         let obs_vector = self.coordinates.to_vector();
 
@@ -105,22 +159,82 @@ impl Observer {
     }*/
 }
 
+/// # Direction and distance of an object seen from an observer
+pub struct LookAngles {
+    pub azimuth: Angle,             // From the North, positive to the East, in [0°, 360°)
+    pub elevation: Angle,           // Above the local horizon
+    pub range: f64,                 // Distance from the observer [km]
+    pub range_rate: f64,            // Time derivative of the range [km/s], positive when moving away
+}
+
+impl LookAngles {
+    /// Look angles of the object at the given state from the observer, in its SEZ frame
+    pub fn from_state(state: &StateVector, observer: &Observer) -> Result<LookAngles>
+    {
+        let mut local = state.clone();
+        local.change_frame(Rc::new(SEZ::new(observer, state.epoch())))?;
+
+        let p = local.position();
+        let v = local.velocity();
+        let range = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+
+        if range == 0.0 {
+            return Err(Error::Math(String::from("The object is at the position of the observer")));
+        }
+
+        Ok(LookAngles {
+            azimuth: Angle::from_radians(p[1].atan2(-p[0])).canonical(),
+            elevation: Angle::from_radians((p[2] / range).asin()),
+            range,
+            range_rate: (p[0] * v[0] + p[1] * v[1] + p[2] * v[2]) / range,
+        })
+    }
+}
+
+impl fmt::Display for LookAngles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "azimuth: {}, elevation: {}, range: {} km, range rate: {} km/s",
+               self.azimuth, self.elevation, self.range, self.range_rate)
+    }
+}
+
 ///
-/// Specifies where the observable object is in GCRF coordinates
+/// Specifies where the observable object is. The frame of the coordinates is given by the state vector
 pub struct Observation {
-    pub time: DateTime<Utc>,        // The time at which this observation is valid
+    pub time: Epoch,                // The time at which this observation is valid
     pub observer: Observer,         // Observer on earth
-    pub position: Point,
-    pub speed: Vector,
-    pub brightness: f64             // Brightness of the satellite
+    pub state: StateVector,         // Position and velocity of the object at `time`
+    pub look_angles: LookAngles,    // Seen from the observer
+    pub magnitude: Option<f64>      // Apparent visual magnitude, None if unknown or not lit by the Sun
+}
+
+impl Observation {
+    /// Observation of an object at the given state. The look angles are computed from the observer.
+    pub fn new(observer: &Observer, state: StateVector) -> Result<Observation>
+    {
+        Ok(Observation {
+            time: state.epoch(),
+            observer: *observer,
+            look_angles: LookAngles::from_state(&state, observer)?,
+            state,
+            magnitude: None,
+        })
+    }
+
+    /// Apparent visual magnitude, NaN if unknown or not lit by the Sun
+    #[deprecated(note = "the `brightness` field was replaced by `magnitude`, None when unknown")]
+    pub fn brightness(&self) -> f64
+    {
+        self.magnitude.unwrap_or(f64::NAN)
+    }
 }
 
 impl fmt::Display for Observation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Observation at {} from {}: position: {} speed: {}",
+        write!(f, "Observation at {} from {}: {} {}",
                self.time,
                self.observer.coordinates,
-               self.position,
-               self.speed)
+               self.look_angles,
+               self.state)
     }
 }
@@ -1,4 +1,4 @@
-use crate::constants::EARTH_EQUATORIAL_RADIUS_KM;
+use crate::constants::EARTH_MEAN_RADIUS_KM;
 use crate::utils::Observer;
 use crate::viewer::camera::CameraControlPlugin;
 use crate::viewer::satellite::{move_satellites, update_satellites, SatelliteMat};
@@ -29,7 +29,7 @@ fn setup(
     // earth
     commands.spawn_bundle(PbrBundle {
         mesh: meshes.add(Mesh::from(shape::UVSphere {
-            radius: EARTH_EQUATORIAL_RADIUS_KM as f32 / 1000.,
+            radius: EARTH_MEAN_RADIUS_KM as f32 / 1000.,
             ..default()
         })),
         material: materials.add(Color::rgb(0.1, 0.1, 0.9).into()),
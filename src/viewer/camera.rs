@@ -1,4 +1,4 @@
-use crate::constants::EARTH_EQUATORIAL_RADIUS_KM;
+use crate::constants::EARTH_MEAN_RADIUS_KM;
 use bevy::prelude::*;
 use bevy_easings::*;
 use smooth_bevy_cameras::{controllers::unreal::*, *};
@@ -33,7 +33,7 @@ fn setup(mut commands: Commands) {
         .spawn_bundle(UnrealCameraBundle::new(
             UnrealCameraController::default(),
             PerspectiveCameraBundle::default(),
-            Vec3::new(0., 0., 3.5 * EARTH_EQUATORIAL_RADIUS_KM as f32 / 1000.),
+            Vec3::new(0., 0., 3.5 * EARTH_MEAN_RADIUS_KM as f32 / 1000.),
             Vec3::new(0., 0., 0.),
         ))
         .insert(TrackedRotator);
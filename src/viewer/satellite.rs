@@ -50,7 +50,7 @@ pub fn move_satellites(
                 continue;
             }
         };
-        tfm.translation = point2vec(&obs.position);
+        tfm.translation = point2vec(&obs.state.position());
     }
 }
 
@@ -61,7 +61,7 @@ pub fn update_satellites(
     mut satellites: Query<(Entity, &mut SatelliteInfo)>,
     mut tle_stream: ResMut<TleStream>,
 ) {
-    if let Ok(mut tles) = tle_stream.next() {
+    if let Ok(Some(mut tles)) = tle_stream.next() {
         for (entity, mut info) in satellites.iter_mut() {
             if let Some(tle) = tles.remove(&info.0.name()) {
                 // Update existing satellites with new TLE
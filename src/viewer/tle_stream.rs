@@ -1,12 +1,14 @@
 use crate::tle::TLE;
 use crate::traits::Observable;
-use crate::BoxResult;
-use reqwest::*;
+use crate::{Error, Result};
+use reqwest::blocking;
 use std::collections::HashMap;
 
 pub struct TleStream {
     consumed: bool,
     url: String,
+    /// Name lines and errors of the TLEs that could not be parsed in the last download
+    rejected: Vec<(String, Error)>,
 }
 
 impl TleStream {
@@ -14,19 +16,25 @@ impl TleStream {
         Self {
             consumed: false,
             url: url.clone(),
+            rejected: Vec::new(),
         }
     }
 
-    pub fn next(&mut self) -> BoxResult<HashMap<String, TLE>> {
+    /// TLEs of the source by name, None if there is no update
+    pub fn next(&mut self) -> Result<Option<HashMap<String, TLE>>> {
         if self.consumed {
             // TODO: someday, periodically check URL for new data...
             // for now: never return results more than once
-            bail!("No update");
+            return Ok(None);
         }
 
         let mut result = HashMap::<String, TLE>::new();
+        self.rejected.clear();
 
-        let r = blocking::get(&self.url)?.text()?;
+        let r = match blocking::get(&self.url).and_then(|response| response.text()) {
+            Ok(r) => r,
+            Err(e) => return Err(Error::Io(std::io::Error::other(e))),
+        };
 
         let mut i = 0;
         let mut tle_lines = vec![];
@@ -42,21 +50,21 @@ impl TleStream {
             i = (i + 1) % 3;
 
             if i == 0 {
-                let tle_result = TLE::from_lines(
-                    tle_lines[1].as_bytes(),
-                    tle_lines[2].as_bytes(),
-                    tle_lines[0].as_bytes(),
-                );
-
-                if let Ok(tle) = tle_result {
-                    result.insert(tle.name(), tle);
-                } else if let Err(e) = tle_result {
-                    println!("{}", e)
+                match TLE::from_lines(tle_lines[1].as_bytes(), tle_lines[2].as_bytes(), tle_lines[0].as_bytes()) {
+                    Ok(tle) => {
+                        result.insert(tle.name(), tle);
+                    },
+                    Err(e) => self.rejected.push((tle_lines[0].trim().to_string(), e)),
                 }
             }
         }
 
         self.consumed = true;
-        Ok(result)
+        Ok(Some(result))
+    }
+
+    /// TLEs of the last download that could not be parsed: name line and error
+    pub fn rejected(&self) -> &[(String, Error)] {
+        &self.rejected
     }
 }
@@ -0,0 +1,84 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Synthetic binary SPK kernels
+ *
+ * Little-endian DAF files with one summary record (record 2), an empty name record (record 3)
+ * and the data of the segments from record 4.
+ */
+
+#![allow(dead_code)]
+
+const RECORD_LENGTH: usize = 1024;
+const WORD_LENGTH: usize = 8;
+
+/// Segment of type 2 or 3 with records of the same size
+pub struct Segment {
+    pub target: i32,
+    pub center: i32,
+    pub frame: i32,
+    pub data_type: i32,
+    /// Start of the first interval [s TDB since J2000]
+    pub init: f64,
+    pub interval_length: f64,
+    /// Middle, radius and coefficients of each interval
+    pub records: Vec<Vec<f64>>,
+}
+
+/// Content of an SPK file with the given segments
+pub fn kernel(segments: &[Segment]) -> Vec<u8>
+{
+    let mut file = vec![0u8; 3 * RECORD_LENGTH];
+    file[..8].copy_from_slice(b"DAF/SPK ");
+    put_int(&mut file, 8, 2);
+    put_int(&mut file, 12, 6);
+    put_int(&mut file, 76, 2);
+    put_int(&mut file, 80, 2);
+    file[88..96].copy_from_slice(b"LTL-IEEE");
+
+    // Summary record: next, previous, count, then the summaries
+    put_double(&mut file, RECORD_LENGTH + 2 * WORD_LENGTH, segments.len() as f64);
+
+    let mut words: Vec<f64> = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let address = 3 * RECORD_LENGTH / WORD_LENGTH + words.len() + 1;
+        for record in &segment.records {
+            words.extend(record);
+        }
+        words.extend([
+            segment.init,
+            segment.interval_length,
+            segment.records[0].len() as f64,
+            segment.records.len() as f64,
+        ]);
+        let end_address = 3 * RECORD_LENGTH / WORD_LENGTH + words.len();
+
+        let offset = RECORD_LENGTH + 3 * WORD_LENGTH + i * 5 * WORD_LENGTH;
+        let end = segment.init + segment.interval_length * segment.records.len() as f64;
+        put_double(&mut file, offset, segment.init);
+        put_double(&mut file, offset + WORD_LENGTH, end);
+        let ints = [segment.target, segment.center, segment.frame, segment.data_type, address as i32, end_address as i32];
+        for (j, value) in ints.iter().enumerate() {
+            put_int(&mut file, offset + 2 * WORD_LENGTH + 4 * j, *value);
+        }
+    }
+
+    for word in words {
+        file.extend(word.to_le_bytes());
+    }
+    file.resize(file.len().div_ceil(RECORD_LENGTH) * RECORD_LENGTH, 0);
+
+    file
+}
+
+pub fn put_int(file: &mut [u8], offset: usize, value: i32)
+{
+    file[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+pub fn put_double(file: &mut [u8], offset: usize, value: f64)
+{
+    file[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
@@ -0,0 +1,182 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Shadow of the Earth
+ *
+ * Positions are built around the Earth-Sun axis, where the borders of the shadows are known:
+ * the cylindrical shadow has the radius of the Earth, and the penumbra of the conical model
+ * spreads around it by the apparent radius of the Sun. Far behind the Earth, the Earth is in
+ * front of the Sun like the Moon during an annular eclipse. The shadow events of the ISS are
+ * checked for order and against the shadow on each side.
+ */
+
+use chrono::{Duration, TimeZone, Utc};
+use tardis::eclipse::{illumination, is_sunlit, shadow, EclipsePredictor, Shadow, ShadowEventKind, ShadowModel};
+use tardis::ellipsoid::Ellipsoid;
+use tardis::sun;
+use tardis::time::Epoch;
+use tardis::tle::TLE;
+
+const ISS: [&str; 3] = [
+    "ISS (ZARYA)",
+    "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+    "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+];
+
+const MODELS: [ShadowModel; 2] = [ShadowModel::Cylindrical, ShadowModel::Conical];
+
+fn epoch() -> Epoch
+{
+    Epoch::from_datetime(Utc.with_ymd_and_hms(2008, 9, 20, 12, 0, 0).unwrap())
+}
+
+fn unit(v: [f64; 3]) -> [f64; 3]
+{
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    v.map(|x| x / length)
+}
+
+/// GCRF position [km] at the given distance along the direction of the Sun (negative behind
+/// the Earth) and distance from the Earth-Sun axis
+fn around_axis(epoch: &Epoch, along: f64, across: f64) -> [f64; 3]
+{
+    let s = unit(sun::position_gcrf(epoch));
+    let p = unit([s[1], -s[0], 0.0]);
+
+    [0, 1, 2].map(|i| along * s[i] + across * p[i])
+}
+
+#[test]
+fn sunlit_side_and_night_side()
+{
+    let epoch = epoch();
+
+    for model in MODELS {
+        let day = around_axis(&epoch, 7000.0, 0.0);
+        assert_eq!(shadow(day, &epoch, model), Shadow::Sunlit);
+        assert_eq!(illumination(day, &epoch, model), 1.0);
+        assert!(is_sunlit(day, &epoch, model));
+
+        let night = around_axis(&epoch, -7000.0, 0.0);
+        assert_eq!(shadow(night, &epoch, model), Shadow::Umbra);
+        assert_eq!(illumination(night, &epoch, model), 0.0);
+        assert!(!is_sunlit(night, &epoch, model));
+
+        // Beside the Earth, out of the shadow
+        let side = around_axis(&epoch, -7000.0, 7000.0);
+        assert_eq!(shadow(side, &epoch, model), Shadow::Sunlit);
+        assert_eq!(illumination(side, &epoch, model), 1.0);
+    }
+}
+
+#[test]
+fn penumbra()
+{
+    let epoch = epoch();
+    let radius = Ellipsoid::WGS84.equatorial_radius;
+
+    // The penumbra is about 2 x 7000 km x 4.65 mrad = 65 km wide, 7000 km behind the Earth
+    let mut previous = 0.0;
+    let mut penumbra = 0;
+    for km in -100..=100 {
+        let position = around_axis(&epoch, -7000.0, radius + km as f64);
+        let fraction = illumination(position, &epoch, ShadowModel::Conical);
+
+        match shadow(position, &epoch, ShadowModel::Conical) {
+            Shadow::Umbra => assert_eq!(fraction, 0.0),
+            Shadow::Sunlit => assert_eq!(fraction, 1.0),
+            Shadow::Penumbra => {
+                assert!(fraction > 0.0 && fraction < 1.0, "{}: {}", km, fraction);
+                penumbra += 1;
+            },
+        }
+        assert!(fraction >= previous, "{}: {} < {}", km, fraction, previous);
+        previous = fraction;
+    }
+    assert!((60..70).contains(&penumbra), "{}", penumbra);
+
+    // On the border of the cylindrical shadow, the center of the Sun is about on the limb of
+    // the Earth (within 1% of the radius of the Sun): about half of its disc is visible
+    let border = around_axis(&epoch, -7000.0, radius);
+    let fraction = illumination(border, &epoch, ShadowModel::Conical);
+    assert!((fraction - 0.5).abs() < 0.01, "{}", fraction);
+
+    // The object is lit in the penumbra
+    let inside = around_axis(&epoch, -7000.0, radius - 5.0);
+    assert_eq!(shadow(inside, &epoch, ShadowModel::Conical), Shadow::Penumbra);
+    assert!(is_sunlit(inside, &epoch, ShadowModel::Conical));
+    assert!(!is_sunlit(inside, &epoch, ShadowModel::Cylindrical));
+}
+
+#[test]
+fn annular_eclipse()
+{
+    let epoch = epoch();
+
+    // 10 million km behind the Earth, the whole Earth is in front of the Sun
+    let position = around_axis(&epoch, -1e7, 0.0);
+    let distance = (sun::position_gcrf(&epoch).iter().map(|x| x * x).sum::<f64>()).sqrt() + 1e7;
+    let a = (sun::SUN_RADIUS_KM as f64 / distance).asin();
+    let b = (Ellipsoid::WGS84.equatorial_radius / 1e7).asin();
+
+    assert_eq!(shadow(position, &epoch, ShadowModel::Conical), Shadow::Penumbra);
+    let fraction = illumination(position, &epoch, ShadowModel::Conical);
+    assert!((fraction - (1.0 - b * b / (a * a))).abs() < 1e-9, "{}", fraction);
+    assert!(fraction > 0.95);
+}
+
+#[test]
+fn shadow_events()
+{
+    let satellite = TLE::from_lines(ISS[1].as_bytes(), ISS[2].as_bytes(), ISS[0].as_bytes()).unwrap();
+    let start = epoch();
+    let end = start + Duration::hours(6);
+
+    let predictor = EclipsePredictor::new(&satellite);
+    let events = predictor.events(start, end).unwrap();
+    let first = events.iter().position(|e| e.kind == ShadowEventKind::PenumbraEntry).unwrap();
+
+    // Every eclipse enters and leaves the penumbra around the umbra
+    let cycle = [
+        ShadowEventKind::PenumbraEntry,
+        ShadowEventKind::UmbraEntry,
+        ShadowEventKind::UmbraExit,
+        ShadowEventKind::PenumbraExit,
+    ];
+    let eclipses = events[first..].chunks_exact(4).count();
+    assert!(eclipses >= 3, "{}", eclipses);
+    for (i, event) in events[first..first + 4 * eclipses].iter().enumerate() {
+        assert_eq!(event.kind, cycle[i % 4]);
+    }
+    for pair in events.windows(2) {
+        assert!(pair[0].time < pair[1].time);
+    }
+
+    for eclipse in events[first..].chunks_exact(4) {
+        // The ISS crosses the penumbra in about 10 s and the umbra in about 35 minutes
+        let penumbra = (eclipse[1].time - eclipse[0].time).num_milliseconds();
+        let umbra = (eclipse[2].time - eclipse[1].time).num_seconds();
+        assert!((5000..20000).contains(&penumbra), "{}", penumbra);
+        assert!((1800..2400).contains(&umbra), "{}", umbra);
+
+        let before = eclipse[0].time - Duration::seconds(1);
+        let after = eclipse[3].time + Duration::seconds(1);
+        assert_eq!(predictor.shadow(before).unwrap(), Shadow::Sunlit);
+        assert_eq!(predictor.shadow(eclipse[0].time + (eclipse[1].time - eclipse[0].time) / 2).unwrap(), Shadow::Penumbra);
+        assert_eq!(predictor.shadow(eclipse[1].time + Duration::seconds(1)).unwrap(), Shadow::Umbra);
+        assert_eq!(predictor.shadow(eclipse[2].time - Duration::seconds(1)).unwrap(), Shadow::Umbra);
+        assert_eq!(predictor.shadow(eclipse[2].time + (eclipse[3].time - eclipse[2].time) / 2).unwrap(), Shadow::Penumbra);
+        assert_eq!(predictor.shadow(after).unwrap(), Shadow::Sunlit);
+
+        assert!(predictor.illumination(eclipse[0].time).unwrap() > 0.999);
+        assert!(predictor.illumination(eclipse[1].time).unwrap() < 0.001);
+    }
+
+    // The cylindrical shadow is inside the penumbra
+    let cylindrical = EclipsePredictor::new(&satellite).with_model(ShadowModel::Cylindrical).events(start, end).unwrap();
+    let entry = cylindrical.iter().find(|e| e.kind == ShadowEventKind::UmbraEntry && e.time > events[first].time).unwrap();
+    assert!(events[first].time < entry.time && entry.time < events[first + 1].time);
+    assert!(cylindrical.iter().all(|e| matches!(e.kind, ShadowEventKind::UmbraEntry | ShadowEventKind::UmbraExit)));
+}
@@ -0,0 +1,83 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Geodetic coordinates
+ *
+ * Round trips between geodetic and cartesian coordinates on the supported ellipsoids, from
+ * the ground to beyond the Moon and up to the poles, and the example 3-3 of Vallado,
+ * Fundamentals of Astrodynamics and Applications.
+ */
+
+use std::f64::consts::PI;
+
+use tardis::ellipsoid::Ellipsoid;
+use tardis::utils::Coordinates;
+
+const ELLIPSOIDS: [Ellipsoid; 3] = [Ellipsoid::WGS84, Ellipsoid::WGS72, Ellipsoid::GRS80];
+
+/// Geodetic latitudes [°], including the poles and their neighbourhood
+const LATITUDES: [f64; 11] = [-90.0, -89.9999, -60.0, -45.0, -0.001, 0.0, 30.0, 70.0, 89.0, 89.9999, 90.0];
+
+/// Longitudes [°], including both sides of the antimeridian
+const LONGITUDES: [f64; 5] = [-180.0, -73.5, 0.0, 2.35, 179.9];
+
+/// Altitudes [km]: below the ground, ground, low orbit, geostationary orbit, Moon
+const ALTITUDES: [f64; 5] = [-0.4, 0.0, 400.0, 35786.0, 384400.0];
+
+#[test]
+fn round_trip()
+{
+    for ellipsoid in ELLIPSOIDS {
+        for lat in LATITUDES {
+            for lon in LONGITUDES {
+                for alt in ALTITUDES {
+                    let position = ellipsoid.to_cartesian(lat.to_radians(), lon.to_radians(), alt);
+                    let (latitude, longitude, altitude) = ellipsoid.to_geodetic(position);
+                    let back = ellipsoid.to_cartesian(latitude, longitude, altitude);
+
+                    // 1 µm, and the longitude is not defined at the poles
+                    assert!((latitude - lat.to_radians()).abs() < 1e-12, "{:?} {} {} {}: {}", ellipsoid, lat, lon, alt, latitude);
+                    assert!((altitude - alt).abs() < 1e-9, "{:?} {} {} {}: {}", ellipsoid, lat, lon, alt, altitude);
+                    if lat.abs() < 90.0 {
+                        let dlon = (longitude - lon.to_radians() + 3.0 * PI) % (2.0 * PI) - PI;
+                        assert!(dlon.abs() < 1e-12, "{:?} {} {} {}: {}", ellipsoid, lat, lon, alt, longitude);
+                    }
+                    for i in 0..3 {
+                        assert!((back[i] - position[i]).abs() < 1e-9, "{:?} {} {} {}: {:?}", ellipsoid, lat, lon, alt, back);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn coordinates()
+{
+    for ellipsoid in ELLIPSOIDS {
+        let coordinates = Coordinates::on_ellipsoid(69.6492, 18.9553, 0.1, ellipsoid);
+        let back = Coordinates::from_cartesian(coordinates.to_cartesian(), ellipsoid);
+
+        assert!((back.latitude() - 69.6492).abs() < 1e-10);
+        assert!((back.longitude() - 18.9553).abs() < 1e-10);
+        assert!((back.altitude() - 0.1).abs() < 1e-9);
+        assert_eq!(back.ellipsoid(), ellipsoid);
+    }
+
+    // The same geodetic coordinates on two ellipsoids are not the same point
+    let wgs72 = Coordinates::on_ellipsoid(45.0, 0.0, 0.0, Ellipsoid::WGS72).to_cartesian();
+    let wgs84 = Coordinates::on_ellipsoid(45.0, 0.0, 0.0, Ellipsoid::WGS84).to_cartesian();
+    assert!((wgs72[0] - wgs84[0]).abs() > 1e-3);
+}
+
+#[test]
+fn vallado_example()
+{
+    let (latitude, longitude, altitude) = Ellipsoid::WGS84.to_geodetic([6524.834, 6862.875, 6448.296]);
+
+    assert!((latitude.to_degrees() - 34.352496).abs() < 1e-6, "{}", latitude.to_degrees());
+    assert!((longitude.to_degrees() - 46.4464).abs() < 1e-4, "{}", longitude.to_degrees());
+    assert!((altitude - 5085.22).abs() < 1e-2, "{}", altitude);
+}
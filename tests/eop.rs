@@ -0,0 +1,73 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Earth Orientation Parameters
+ *
+ * The celestial pole offsets of finals2000A.all (dX, dY) are converted to corrections of the
+ * IAU 1980 nutation (dPsi, dEps) for the FK5 reduction, and back.
+ */
+
+use std::f64::consts::PI;
+use tardis::eop::{EarthOrientation, EopFormat};
+
+/// First entry of finals2000A.all (1973-01-02)
+const FINALS_2000A: &str = "73 1 2 41684.00 I  0.120733 0.009786  0.136966 0.015902  I 0.8084178 0.0002710  0.0000 0.1916  P    -0.766    0.199    -0.720    0.300   .143000   .137000   .8075000    -0.766    -0.720  ";
+
+const JD: f64 = 2400000.5 + 41684.0;
+const ARCSEC_TO_RAD: f64 = PI / 648000.0;
+const MILLIARCSEC_TO_RAD: f64 = PI / 648000000.0;
+const MICROARCSEC_TO_RAD: f64 = PI / 648000000000.0;
+
+/// Line of finals.all with the same parameters and the given dPsi, dEps [mas]
+fn finals_line(dpsi: f64, deps: f64) -> String
+{
+    format!("{}{:9.3}{:9.3} {:9.3}{:9.3}", &FINALS_2000A[..97], dpsi, 0.0, deps, 0.0)
+}
+
+#[test]
+fn finals_2000a_line()
+{
+    let table = EarthOrientation::parse(FINALS_2000A, EopFormat::Finals2000A).unwrap();
+    let values = table.at(JD);
+
+    assert_eq!(values.x_p, 0.120733 * ARCSEC_TO_RAD);
+    assert_eq!(values.y_p, 0.136966 * ARCSEC_TO_RAD);
+    assert_eq!(values.ut1_utc, 0.8084178);
+    assert_eq!(values.lod, 0.0);
+    assert_eq!(values.dx, -0.766 * MILLIARCSEC_TO_RAD);
+    assert_eq!(values.dy, -0.720 * MILLIARCSEC_TO_RAD);
+
+    // The IAU 1980 nutation needs corrections of tens of mas in 1973, mostly for the
+    // precession rate of the IAU 1976 model
+    assert!(values.dpsi.abs() > 10.0 * MILLIARCSEC_TO_RAD && values.dpsi.abs() < 100.0 * MILLIARCSEC_TO_RAD);
+    assert!(values.deps.abs() < 20.0 * MILLIARCSEC_TO_RAD);
+}
+
+#[test]
+fn pole_offsets_round_trip()
+{
+    let iau2000 = EarthOrientation::parse(FINALS_2000A, EopFormat::Finals2000A).unwrap().at(JD);
+
+    // finals.all gives dPsi, dEps with 1 µas
+    let dpsi = (iau2000.dpsi / MILLIARCSEC_TO_RAD * 1000.0).round() / 1000.0;
+    let deps = (iau2000.deps / MILLIARCSEC_TO_RAD * 1000.0).round() / 1000.0;
+    let iau1980 = EarthOrientation::parse(&finals_line(dpsi, deps), EopFormat::Finals).unwrap().at(JD);
+
+    assert_eq!(iau1980.dpsi, dpsi * MILLIARCSEC_TO_RAD);
+    assert_eq!(iau1980.deps, deps * MILLIARCSEC_TO_RAD);
+    assert!((iau1980.dx - iau2000.dx).abs() < MICROARCSEC_TO_RAD);
+    assert!((iau1980.dy - iau2000.dy).abs() < MICROARCSEC_TO_RAD);
+}
+
+#[test]
+fn missing_pole_offsets()
+{
+    // Far predictions have no celestial pole offsets: the IAU 1980 model is not corrected
+    // and the IAU 2006/2000A model is used as is
+    let line = &FINALS_2000A[..97];
+    let values = EarthOrientation::parse(line, EopFormat::Finals).unwrap().at(JD);
+
+    assert_eq!((values.dpsi, values.deps, values.dx, values.dy), (0.0, 0.0, 0.0, 0.0));
+}
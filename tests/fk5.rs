@@ -0,0 +1,190 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* IAU-76/FK5 reduction
+ *
+ * Intermediate states of the example 3-15 of Vallado, Fundamentals of Astrodynamics and
+ * Applications (also in Vallado et al., Revisiting Spacetrack Report #3, AIAA 2006-6753), at
+ * 2004 April 6 07:51:28.386009 UTC with the EOP of that day.
+ *
+ * The TOD and MOD states of the example are computed without the corrections dPsi, dEps to
+ * the nutation, and its "J2000" state is the precession of that MOD state: it is GCRF here
+ * when the corrections are 0. The J2000 frame of tardis is GCRF rotated by the frame bias,
+ * which is checked against the matrix of ERFA.
+ */
+
+use std::rc::Rc;
+use std::sync::{Mutex, MutexGuard};
+use chrono::{Duration, TimeZone, Utc};
+use tardis::eop::{set_earth_orientation, EarthOrientation, EopFormat};
+use tardis::frames::{convert_direction, convert_state, GCRF, ITRF, J2000, MOD, PEF, TEME, TOD};
+use tardis::geometry::StateVector;
+use tardis::time::Epoch;
+use tardis::traits::{Framable, Frame};
+use tardis::Error;
+
+/// Position [km] and velocity [km/s]
+type State = ([f64; 3], [f64; 3]);
+
+const ITRF_STATE: State = ([-1033.4793830, 7901.2952754, 6380.3565958], [-3.225636520, -2.872451450, 5.531924446]);
+const PEF_STATE: State = ([-1033.4750313, 7901.3055856, 6380.3445328], [-3.225632747, -2.872442511, 5.531931288]);
+const TEME_STATE: State = ([5094.18016210, 6127.64465950, 6380.34453270], [-4.746131487, 0.785818041, 5.531931288]);
+const TOD_STATE: State = ([5094.5147804, 6127.3664612, 6380.3445328], [-4.746088567, 0.786077222, 5.531931288]);
+const MOD_STATE: State = ([5094.0290167, 6127.8709363, 6380.2478885], [-4.746262495, 0.786014149, 5.531791025]);
+/// "J2000" of the example
+const FK5_STATE: State = ([5102.5096, 6123.01152, 6378.1363], [-4.7432196, 0.7905366, 5.5337561]);
+const GCRF_STATE: State = ([5102.508958, 6123.011401, 6378.136928], [-4.743220156, 0.790536497, 5.533755728]);
+
+/// The EOP table is global: the tests that install one run one at a time
+static EOP: Mutex<()> = Mutex::new(());
+
+fn epoch() -> Epoch
+{
+    Epoch::from_datetime(Utc.with_ymd_and_hms(2004, 4, 6, 7, 51, 28).unwrap() + Duration::nanoseconds(386009000))
+}
+
+/// Install the EOP of 2004 April 6 as a line of finals.all: xp = -0.140682", yp = 0.333309",
+/// UT1 - UTC = -0.4399619 s, LOD = 1.5563 ms, with the given dPsi, dEps [mas]
+/// (-52.195 mas and -3.875 mas that day)
+fn install_eop(dpsi: f64, deps: f64) -> MutexGuard<'static, ()>
+{
+    let guard = EOP.lock().unwrap_or_else(|e| e.into_inner());
+
+    let line = format!("{:<7}{:8.2}{:3}{:9.6}{:10}{:9.6}{:12}{:10.7}{:11}{:7.4}{:11}{:9.3}{:10}{:9.3}",
+                       " 4 4 6", 53101.0, " I ", -0.140682, "", 0.333309, "", -0.4399619, "", 1.5563, "",
+                       dpsi, "", deps);
+    set_earth_orientation(EarthOrientation::parse(&line, EopFormat::Finals).unwrap());
+
+    guard
+}
+
+/// Convert the state and compare it to the expected one, with the given tolerances on the
+/// positions [km] and velocities [km/s]
+fn assert_converted(from: &dyn Frame, to: &dyn Frame, state: State, expected: State, tolerance: (f64, f64))
+{
+    let (p, v) = convert_state(from, to, state.0, state.1).unwrap();
+
+    for i in 0..3 {
+        assert!((p[i] - expected.0[i]).abs() < tolerance.0, "{} -> {}: {:?} != {:?}", from.name(), to.name(), p, expected.0);
+        assert!((v[i] - expected.1[i]).abs() < tolerance.1, "{} -> {}: {:?} != {:?}", from.name(), to.name(), v, expected.1);
+    }
+}
+
+#[test]
+fn teme_to_itrf()
+{
+    let _eop = install_eop(-52.195, -3.875);
+    let epoch = epoch();
+
+    // Polar motion: rounded to 0.1 mm and 1 µm/s
+    assert_converted(&PEF::new(epoch), &ITRF::new(epoch), PEF_STATE, ITRF_STATE, (2e-7, 2e-9));
+
+    // The sidereal time of the example differs by about 1 nrad (14 µs of UT1)
+    let tolerance = (1e-5, 1e-8);
+    assert_converted(&TEME::new(epoch), &PEF::new(epoch), TEME_STATE, PEF_STATE, tolerance);
+    assert_converted(&TEME::new(epoch), &ITRF::new(epoch), TEME_STATE, ITRF_STATE, tolerance);
+    assert_converted(&ITRF::new(epoch), &TEME::new(epoch), ITRF_STATE, TEME_STATE, tolerance);
+}
+
+#[test]
+fn state_vector_to_itrf()
+{
+    let _eop = install_eop(-52.195, -3.875);
+    let epoch = epoch();
+
+    let mut state = StateVector::new(TEME_STATE.0, TEME_STATE.1, epoch, Rc::new(TEME::new(epoch))).unwrap();
+    state.change_frame(Rc::new(ITRF::new(epoch))).unwrap();
+
+    let p = state.position();
+    let v = state.velocity();
+    for i in 0..3 {
+        assert!((p[i] - ITRF_STATE.0[i]).abs() < 1e-5, "{}", state);
+        assert!((v[i] - ITRF_STATE.1[i]).abs() < 1e-8, "{}", state);
+    }
+
+    // Rotating the velocity like a direction misses ω × r: about 0.6 km/s here
+    let rotated = convert_direction(&TEME::new(epoch), &ITRF::new(epoch), TEME_STATE.1).unwrap();
+    assert!((rotated[0] - ITRF_STATE.1[0]).abs() > 0.5, "{:?}", rotated);
+}
+
+#[test]
+fn state_vector_at_another_epoch()
+{
+    let epoch = epoch();
+    let mut state = StateVector::new(TEME_STATE.0, TEME_STATE.1, epoch, Rc::new(TEME::new(epoch))).unwrap();
+
+    let later = epoch + Duration::seconds(1);
+    assert!(matches!(state.change_frame(Rc::new(ITRF::new(later))), Err(Error::Frame(_))));
+    assert!(matches!(state.change_frame(Rc::new(TEME::new(later))), Err(Error::Frame(_))));
+
+    // Unchanged
+    assert_eq!(state.frame().name(), "TEME");
+    for i in 0..3 {
+        assert_eq!(state.position()[i], TEME_STATE.0[i]);
+        assert_eq!(state.velocity()[i], TEME_STATE.1[i]);
+    }
+}
+
+#[test]
+fn state_vector_in_a_frame_at_another_epoch()
+{
+    let epoch = epoch();
+    let later = epoch + Duration::seconds(1);
+
+    let state = StateVector::new(TEME_STATE.0, TEME_STATE.1, epoch, Rc::new(TEME::new(later)));
+    assert!(matches!(state, Err(Error::Frame(_))));
+
+    // Epoch-independent frame
+    let state = StateVector::new(FK5_STATE.0, FK5_STATE.1, later, Rc::new(J2000::new())).unwrap();
+    assert_eq!(state.epoch(), later);
+}
+
+#[test]
+fn uncorrected_reduction()
+{
+    let _eop = install_eop(0.0, 0.0);
+    let epoch = epoch();
+
+    let tolerance = (2e-7, 2e-9);
+    assert_converted(&TEME::new(epoch), &TOD::new(epoch), TEME_STATE, TOD_STATE, tolerance);
+    assert_converted(&TOD::new(epoch), &MOD::new(epoch), TOD_STATE, MOD_STATE, tolerance);
+
+    // Sidereal time as above
+    assert_converted(&PEF::new(epoch), &TOD::new(epoch), PEF_STATE, TOD_STATE, (1e-5, 1e-8));
+
+    // Rounded to 0.1 mm and 0.1 mm/s
+    let tolerance = (2e-7, 1e-7);
+    assert_converted(&MOD::new(epoch), &GCRF::new(epoch), MOD_STATE, FK5_STATE, tolerance);
+    assert_converted(&GCRF::new(epoch), &MOD::new(epoch), FK5_STATE, MOD_STATE, tolerance);
+}
+
+#[test]
+fn corrected_reduction()
+{
+    let _eop = install_eop(-52.195, -3.875);
+    let epoch = epoch();
+
+    assert_converted(&ITRF::new(epoch), &GCRF::new(epoch), ITRF_STATE, GCRF_STATE, (1e-5, 1e-8));
+    assert_converted(&GCRF::new(epoch), &ITRF::new(epoch), GCRF_STATE, ITRF_STATE, (1e-5, 1e-8));
+
+    // The corrections move the state by about 1 m
+    let (p, _) = convert_state(&ITRF::new(epoch), &GCRF::new(epoch), ITRF_STATE.0, ITRF_STATE.1).unwrap();
+    assert!((p[0] - FK5_STATE.0[0]).abs() > 5e-4);
+}
+
+#[test]
+fn frame_bias()
+{
+    let epoch = epoch();
+
+    // First column of the frame bias matrix of eraBp00. ERFA derives ξ0 from the bias in
+    // longitude: it differs from the -16.617 mas of the IERS Conventions by 0.2 µas.
+    let x = convert_direction(&GCRF::new(epoch), &J2000::new(), [1.0, 0.0, 0.0]).unwrap();
+    let expected = [0.999_999_999_999_994_2, 0.707_827_947_785_733_7e-7, -0.805_621_738_098_697_2e-7];
+
+    for i in 0..3 {
+        assert!((x[i] - expected[i]).abs() < 1e-12, "{:?} != {:?}", x, expected);
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Barycentric frame
+ *
+ * The ICRS frame is GCRF translated by the state of the Earth relatively to the solar system
+ * barycenter, read from the installed SPK kernel.
+ */
+
+mod common;
+
+use common::Segment;
+use tardis::frames::{convert_state, GCRF, ICRS};
+use tardis::spk::{self, SpkFile};
+use tardis::time::{Epoch, JD_J2000, TimeScale};
+use tardis::Error;
+
+#[test]
+fn icrs()
+{
+    let epoch = Epoch::from_julian_day(JD_J2000, TimeScale::TDB);
+    let position = [7000.0, 0.0, 0.0];
+    let velocity = [0.0, 7.5, 0.0];
+
+    spk::clear_ephemeris();
+    match convert_state(&GCRF::new(epoch), &ICRS::new(epoch), position, velocity) {
+        Err(Error::Ephemeris(_)) => (),
+        Err(e) => panic!("Unexpected error: {}", e),
+        Ok(_) => panic!("Converted to ICRS without ephemeris"),
+    }
+
+    // Earth at a constant velocity around J2000
+    let radius = 1e6;
+    let earth = [-2.6e7, 1.3e8, 5.6e7];
+    let earth_velocity = [-29.8, -5.0, -2.2];
+    let mut record = vec![0.0, radius];
+    for i in 0..3 {
+        record.extend([earth[i], earth_velocity[i] * radius]);
+    }
+    let kernel = common::kernel(&[Segment {
+        target: spk::EARTH,
+        center: spk::SOLAR_SYSTEM_BARYCENTER,
+        frame: 1,
+        data_type: 2,
+        init: -radius,
+        interval_length: 2.0 * radius,
+        records: vec![record],
+    }]);
+    spk::set_ephemeris(SpkFile::parse(kernel).unwrap());
+
+    let (p, v) = convert_state(&GCRF::new(epoch), &ICRS::new(epoch), position, velocity).unwrap();
+    for i in 0..3 {
+        assert!((p[i] - position[i] - earth[i]).abs() < 1e-3, "{:?}", p);
+        assert!((v[i] - velocity[i] - earth_velocity[i]).abs() < 1e-9, "{:?}", v);
+    }
+
+    let (p, v) = convert_state(&ICRS::new(epoch), &GCRF::new(epoch), p, v).unwrap();
+    for i in 0..3 {
+        assert!((p[i] - position[i]).abs() < 1e-6, "{:?}", p);
+        assert!((v[i] - velocity[i]).abs() < 1e-9, "{:?}", v);
+    }
+
+    spk::clear_ephemeris();
+}
@@ -0,0 +1,132 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Leap second tables
+ *
+ * The IERS files in data/ are parsed and TAI - UTC is checked inside each interval and on
+ * both sides of each boundary against the values published by the USNO.
+ */
+
+use tardis::time::LeapSeconds;
+
+const TAI_UTC_DAT: &str = include_str!("data/tai-utc.dat");
+const LEAP_SECOND_DAT: &str = include_str!("data/Leap_Second.dat");
+
+/// Start [JD UTC], TAI - UTC [s], reference MJD and drift [s/day] of each interval
+const PUBLISHED: [(f64, f64, f64, f64); 41] = [
+    (2437300.5, 1.4228180, 37300.0, 0.001296),
+    (2437512.5, 1.3728180, 37300.0, 0.001296),
+    (2437665.5, 1.8458580, 37665.0, 0.0011232),
+    (2438334.5, 1.9458580, 37665.0, 0.0011232),
+    (2438395.5, 3.2401300, 38761.0, 0.001296),
+    (2438486.5, 3.3401300, 38761.0, 0.001296),
+    (2438639.5, 3.4401300, 38761.0, 0.001296),
+    (2438761.5, 3.5401300, 38761.0, 0.001296),
+    (2438820.5, 3.6401300, 38761.0, 0.001296),
+    (2438942.5, 3.7401300, 38761.0, 0.001296),
+    (2439004.5, 3.8401300, 38761.0, 0.001296),
+    (2439126.5, 4.3131700, 39126.0, 0.002592),
+    (2439887.5, 4.2131700, 39126.0, 0.002592),
+    (2441317.5, 10.0, 41317.0, 0.0),
+    (2441499.5, 11.0, 41317.0, 0.0),
+    (2441683.5, 12.0, 41317.0, 0.0),
+    (2442048.5, 13.0, 41317.0, 0.0),
+    (2442413.5, 14.0, 41317.0, 0.0),
+    (2442778.5, 15.0, 41317.0, 0.0),
+    (2443144.5, 16.0, 41317.0, 0.0),
+    (2443509.5, 17.0, 41317.0, 0.0),
+    (2443874.5, 18.0, 41317.0, 0.0),
+    (2444239.5, 19.0, 41317.0, 0.0),
+    (2444786.5, 20.0, 41317.0, 0.0),
+    (2445151.5, 21.0, 41317.0, 0.0),
+    (2445516.5, 22.0, 41317.0, 0.0),
+    (2446247.5, 23.0, 41317.0, 0.0),
+    (2447161.5, 24.0, 41317.0, 0.0),
+    (2447892.5, 25.0, 41317.0, 0.0),
+    (2448257.5, 26.0, 41317.0, 0.0),
+    (2448804.5, 27.0, 41317.0, 0.0),
+    (2449169.5, 28.0, 41317.0, 0.0),
+    (2449534.5, 29.0, 41317.0, 0.0),
+    (2450083.5, 30.0, 41317.0, 0.0),
+    (2450630.5, 31.0, 41317.0, 0.0),
+    (2451179.5, 32.0, 41317.0, 0.0),
+    (2453736.5, 33.0, 41317.0, 0.0),
+    (2454832.5, 34.0, 41317.0, 0.0),
+    (2456109.5, 35.0, 41317.0, 0.0),
+    (2457204.5, 36.0, 41317.0, 0.0),
+    (2457754.5, 37.0, 41317.0, 0.0),
+];
+
+/// Expiration of Leap_Second.dat: 28 June 2027
+const EXPIRES: f64 = 2461584.5;
+
+/// A tenth of a second before a boundary [day]
+const BEFORE: f64 = 0.1 / 86400.0;
+
+fn published(interval: usize, jd_utc: f64) -> f64
+{
+    let (_, offset, mjd_ref, rate) = PUBLISHED[interval];
+    offset + (jd_utc - 2400000.5 - mjd_ref) * rate
+}
+
+/// Check the table against the published intervals, from the given one
+fn check(table: &LeapSeconds, first: usize)
+{
+    for (i, &(start, ..)) in PUBLISHED.iter().enumerate().skip(first) {
+        let end = match PUBLISHED.get(i + 1) {
+            Some(next) => next.0,
+            None => start + 3650.0,
+        };
+
+        for jd in [start, (start + end) / 2.0, end - BEFORE] {
+            let value = table.tai_minus_utc(jd);
+            assert!((value - published(i, jd)).abs() < 1e-9,
+                    "TAI - UTC = {} instead of {} at JD {}", value, published(i, jd), jd);
+        }
+
+        if i > first {
+            let value = table.tai_minus_utc(start - BEFORE);
+            assert!((value - published(i - 1, start - BEFORE)).abs() < 1e-9,
+                    "TAI - UTC = {} before the boundary at JD {}", value, start);
+        }
+    }
+}
+
+#[test]
+fn tai_utc_dat()
+{
+    let table = LeapSeconds::parse(TAI_UTC_DAT).unwrap();
+
+    check(&table, 0);
+    assert_eq!(table.expires(), None);
+}
+
+#[test]
+fn leap_second_dat()
+{
+    let table = LeapSeconds::parse(LEAP_SECOND_DAT).unwrap();
+
+    // The file starts in 1972, after the drifting intervals
+    check(&table, 13);
+    assert_eq!(table.expires(), Some(EXPIRES));
+}
+
+#[test]
+fn builtin()
+{
+    let table = LeapSeconds::builtin();
+
+    check(&table, 0);
+    assert_eq!(table.expires(), Some(EXPIRES));
+}
+
+#[test]
+fn unsorted_table()
+{
+    let mut lines: Vec<&str> = TAI_UTC_DAT.lines().collect();
+    lines.swap(20, 21);
+
+    assert!(LeapSeconds::parse(&lines.join("\n")).is_err());
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Look angles
+ *
+ * Azimuth and elevation against published examples:
+ *  - Venus from the US Naval Observatory (Meeus, Astronomical Algorithms, example 13.b)
+ *  - the Sun from Golden, Colorado (Reda & Andreas, Solar Position Algorithm for Solar
+ *    Radiation Applications, NREL/TP-560-34302, table A5.1)
+ * The range and the range rate of a satellite are checked against the geometry of its
+ * sub-satellite point and the finite differences of the range.
+ */
+
+use std::rc::Rc;
+use chrono::{Duration, TimeZone, Utc};
+use tardis::ellipsoid::Ellipsoid;
+use tardis::frames::{ITRF, TOD};
+use tardis::geometry::StateVector;
+use tardis::sun::Sun;
+use tardis::time::Epoch;
+use tardis::tle::TLE;
+use tardis::traits::{Framable, Observable};
+use tardis::utils::{Coordinates, LookAngles, Observer};
+
+const ISS: [&str; 3] = [
+    "ISS (ZARYA)",
+    "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+    "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+];
+
+#[test]
+fn venus_from_washington()
+{
+    // Apparent right ascension and declination of Venus on 1987 April 10 at 19:21 UT
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(1987, 4, 10, 19, 21, 0).unwrap());
+    let ra = (23.0 + 9.0 / 60.0 + 16.641 / 3600.0) * 15.0_f64.to_radians();
+    let dec = -(6.0 + 43.0 / 60.0 + 11.61 / 3600.0_f64).to_radians();
+
+    // Far enough for the parallax to be negligible
+    let distance = 1e12;
+    let position = [distance * dec.cos() * ra.cos(), distance * dec.cos() * ra.sin(), distance * dec.sin()];
+    let state = StateVector::new(position, [0.0; 3], epoch, Rc::new(TOD::new(epoch))).unwrap();
+
+    let lat = 38.0 + 55.0 / 60.0 + 17.0 / 3600.0;
+    let lon = -(77.0 + 3.0 / 60.0 + 56.0 / 3600.0);
+    let observer = Observer::new(Coordinates::new(lat, lon));
+
+    let angles = LookAngles::from_state(&state, &observer).unwrap();
+
+    // Meeus gives the azimuth from the South: 68.0337°
+    assert!((angles.azimuth.degrees() - 248.0337).abs() < 5e-4, "{}", angles);
+    assert!((angles.elevation.degrees() - 15.1249).abs() < 5e-4, "{}", angles);
+}
+
+#[test]
+fn sun_from_golden()
+{
+    // 2003 October 17 at 12:30:30 local time (UTC-7)
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2003, 10, 17, 19, 30, 30).unwrap());
+    let observer = Observer::new(Coordinates::with_altitude(39.742476, -105.1786, 1.83014));
+
+    let angles = Sun::new().observation_at(&observer, epoch).unwrap().look_angles;
+
+    // Topocentric elevation without the atmospheric refraction
+    assert!((angles.azimuth.degrees() - 194.340241).abs() < 5e-4, "{}", angles);
+    assert!((angles.elevation.degrees() - 39.872046).abs() < 5e-4, "{}", angles);
+    assert!((angles.range / 149597870.7 - 0.9965422974).abs() < 1e-4, "{}", angles);
+}
+
+#[test]
+fn satellite_overhead()
+{
+    let satellite = TLE::from_lines(ISS[1].as_bytes(), ISS[2].as_bytes(), ISS[0].as_bytes()).unwrap();
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2008, 9, 20, 13, 0, 0).unwrap());
+
+    let mut state = satellite.observation_at(&Observer::earth(), epoch).unwrap().state;
+    state.change_frame(Rc::new(ITRF::new(epoch))).unwrap();
+    let p = state.position();
+    let point = Coordinates::from_cartesian([p[0], p[1], p[2]], Ellipsoid::WGS84);
+
+    // Seen from the sub-satellite point, the satellite is at the zenith, at its altitude
+    let observer = Observer::new(Coordinates::new(point.latitude(), point.longitude()));
+    let angles = satellite.observation_at(&observer, epoch).unwrap().look_angles;
+
+    assert!(angles.elevation.degrees() > 90.0 - 1e-6, "{}", angles);
+    assert!((angles.range - point.altitude()).abs() < 1e-6, "{}", angles);
+}
+
+#[test]
+fn satellite_range_rate()
+{
+    let satellite = TLE::from_lines(ISS[1].as_bytes(), ISS[2].as_bytes(), ISS[0].as_bytes()).unwrap();
+    let observer = Observer::new(Coordinates::with_altitude(48.8566, 2.3522, 0.035));
+    let start = Utc.with_ymd_and_hms(2008, 9, 20, 12, 0, 0).unwrap();
+    let range = |seconds: i64| {
+        let epoch = Epoch::from_datetime(start + Duration::seconds(seconds));
+        satellite.observation_at(&observer, epoch).unwrap().look_angles.range
+    };
+
+    // Over a whole orbit, satellite below and above the horizon
+    for seconds in (0..5400).step_by(300) {
+        let angles = satellite.observation_at(&observer, Epoch::from_datetime(start + Duration::seconds(seconds))).unwrap().look_angles;
+        let rate = (range(seconds + 1) - range(seconds - 1)) / 2.0;
+
+        assert!((angles.range_rate - rate).abs() < 1e-4, "{} s: {} {}", seconds, angles, rate);
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Apparent magnitude of satellites
+ *
+ * The intrinsic magnitude is the magnitude at 1000 km with a phase angle of 90°: the ISS
+ * (-1.8) is seen at -1.8 in these conditions.
+ */
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+use chrono::{TimeZone, Utc};
+use tardis::frames::{convert_position, GCRF, SEZ};
+use tardis::geometry::StateVector;
+use tardis::magnitude::{apparent_magnitude, diffuse_sphere_phase, satellite_magnitude};
+use tardis::sun;
+use tardis::time::Epoch;
+use tardis::utils::{Coordinates, Observation, Observer};
+
+const ISS_MAGNITUDE: f64 = -1.8;
+
+#[test]
+fn apparent()
+{
+    assert!((apparent_magnitude(ISS_MAGNITUDE, 1000.0, PI / 2.0) - ISS_MAGNITUDE).abs() < 1e-12);
+
+    // Twice as far: 4 times fainter
+    let m = apparent_magnitude(ISS_MAGNITUDE, 2000.0, PI / 2.0);
+    assert!((m - ISS_MAGNITUDE - 5.0 * 2.0_f64.log10()).abs() < 1e-12, "{}", m);
+
+    // Fully illuminated: π times brighter than half illuminated
+    let m = apparent_magnitude(ISS_MAGNITUDE, 1000.0, 0.0);
+    assert!((m - ISS_MAGNITUDE + 2.5 * PI.log10()).abs() < 1e-12, "{}", m);
+
+    assert!((diffuse_sphere_phase(0.0) - 1.0).abs() < 1e-15);
+    assert!((diffuse_sphere_phase(PI / 2.0) - 1.0 / PI).abs() < 1e-15);
+    assert!(diffuse_sphere_phase(PI).abs() < 1e-15);
+}
+
+#[test]
+fn satellite()
+{
+    // Equinox: the Sun is close to the zenith of the observer
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap());
+    let observer = Observer::new(Coordinates::new(0.0, 0.0));
+    let gcrf = GCRF::new(epoch);
+
+    let site = convert_position(&SEZ::new(&observer, epoch), &gcrf, [0.0, 0.0, 0.0]).unwrap();
+    let sun = sun::position_gcrf(&epoch);
+
+    // 1000 km from the observer, perpendicularly to the Sun: phase angle of 90°
+    let normal = [
+        site[1] * sun[2] - site[2] * sun[1],
+        site[2] * sun[0] - site[0] * sun[2],
+        site[0] * sun[1] - site[1] * sun[0],
+    ];
+    let norm = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+    let position = [0, 1, 2].map(|i| site[i] + 1000.0 * normal[i] / norm);
+
+    let state = StateVector::new(position, [0.0; 3], epoch, Rc::new(gcrf)).unwrap();
+    let observation = Observation::new(&observer, state).unwrap();
+    assert!((observation.look_angles.range - 1000.0).abs() < 1e-6);
+
+    let m = satellite_magnitude(ISS_MAGNITUDE, &observation).unwrap().unwrap();
+    assert!((m - ISS_MAGNITUDE).abs() < 1e-3, "{}", m);
+
+    // On the night side, in the shadow of the Earth
+    let position = site.map(|x| -1.2 * x);
+    let state = StateVector::new(position, [0.0; 3], epoch, Rc::new(gcrf)).unwrap();
+    let observation = Observation::new(&observer, state).unwrap();
+
+    assert_eq!(satellite_magnitude(ISS_MAGNITUDE, &observation).unwrap(), None);
+}
+
+#[test]
+#[allow(deprecated)]
+fn brightness()
+{
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap());
+    let state = StateVector::new([7000.0, 0.0, 0.0], [0.0; 3], epoch, Rc::new(GCRF::new(epoch))).unwrap();
+    let mut observation = Observation::new(&Observer::new(Coordinates::new(0.0, 0.0)), state).unwrap();
+
+    assert!(observation.brightness().is_nan());
+
+    observation.magnitude = Some(ISS_MAGNITUDE);
+    assert_eq!(observation.brightness(), ISS_MAGNITUDE);
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Moon
+ *
+ * Distance and illuminated fraction of the examples 47.a and 48.a of Meeus, Astronomical
+ * Algorithms (1992 April 12 at 0h TD), the phases of January 2024, and the moonrises and
+ * moonsets of that month in Paris.
+ */
+
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use tardis::moon::{geometric_state, illuminated_fraction, phase_angle, rise_set, Moon, MOONRISE_ELEVATION};
+use tardis::passes::{PassEvent, RiseSet};
+use tardis::sun::sun_times;
+use tardis::time::{Epoch, TimeScale};
+use tardis::traits::Observable;
+use tardis::utils::{Coordinates, Observer};
+
+fn paris() -> Observer
+{
+    Observer::new(Coordinates::with_altitude(48.8566, 2.3522, 0.035))
+}
+
+fn meeus_epoch() -> Epoch
+{
+    Epoch::from_julian_day(2448724.5, TimeScale::TT)
+}
+
+#[test]
+fn distance()
+{
+    let p = geometric_state(&meeus_epoch()).unwrap().position();
+    let distance = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+
+    assert!((distance - 368409.7).abs() < 0.1, "{}", distance);
+}
+
+#[test]
+fn illuminated_fraction_of_the_disc()
+{
+    // Example 48.a: i = 69.0756°, k = 0.6786, from lower accuracy positions of the Sun
+    let epoch = meeus_epoch();
+    assert!((phase_angle(&epoch).degrees() - 69.0756).abs() < 0.01, "{}", phase_angle(&epoch).degrees());
+    assert!((illuminated_fraction(&epoch) - 0.6786).abs() < 1e-4, "{}", illuminated_fraction(&epoch));
+
+    // New Moon on 2024 January 11 at 11:57 UTC, full Moon on January 25 at 17:54 UTC
+    let new_moon = Epoch::from_datetime(Utc.with_ymd_and_hms(2024, 1, 11, 11, 57, 0).unwrap());
+    let full_moon = Epoch::from_datetime(Utc.with_ymd_and_hms(2024, 1, 25, 17, 54, 0).unwrap());
+
+    // The latitude of the Moon reaches 5°: the fraction is within (1 - cos 5°) / 2 = 0.0019 of
+    // 0 and 1 at the syzygies
+    assert!(illuminated_fraction(&new_moon) < 2e-3, "{}", illuminated_fraction(&new_moon));
+    assert!(illuminated_fraction(&full_moon) > 0.998, "{}", illuminated_fraction(&full_moon));
+
+    // First quarter on January 18 at 03:53 UTC
+    let quarter = Epoch::from_datetime(Utc.with_ymd_and_hms(2024, 1, 18, 3, 53, 0).unwrap());
+    assert!((illuminated_fraction(&quarter) - 0.5).abs() < 0.01, "{}", illuminated_fraction(&quarter));
+}
+
+#[test]
+fn moonrise_and_moonset()
+{
+    let observer = paris();
+    let mut without_moonrise = 0;
+    let mut without_moonset = 0;
+
+    for day in 1..=31 {
+        let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        match rise_set(&observer, date).unwrap() {
+            RiseSet::Crossings { rise, set } => {
+                match rise {
+                    Some(rise) => assert_crossing(&observer, rise, true),
+                    None => without_moonrise += 1,
+                }
+                match set {
+                    Some(set) => assert_crossing(&observer, set, false),
+                    None => without_moonset += 1,
+                }
+            },
+            _ => panic!("the Moon rises or sets every day in Paris"),
+        }
+    }
+
+    // The Moon rises about 50 minutes later each day: one day of the month has no moonrise,
+    // and one has no moonset
+    assert_eq!(without_moonrise, 1);
+    assert_eq!(without_moonset, 1);
+
+    // The full Moon rises around the sunset and sets around the sunrise
+    let date = NaiveDate::from_ymd_opt(2024, 1, 25).unwrap();
+    let sun = match sun_times(&observer, date).unwrap().sunrise_sunset {
+        RiseSet::Crossings { rise: Some(rise), set: Some(set) } => (rise.time, set.time),
+        _ => panic!("sunrise and sunset expected"),
+    };
+    match rise_set(&observer, date).unwrap() {
+        RiseSet::Crossings { rise: Some(rise), set: Some(set) } => {
+            assert!((rise.time - sun.1).num_minutes().abs() < 60, "{} {}", rise.time, sun.1);
+            assert!((set.time - sun.0).num_minutes().abs() < 60, "{} {}", set.time, sun.0);
+        },
+        _ => panic!("moonrise and moonset expected"),
+    }
+}
+
+/// The center of the Moon crosses the elevation of the moonrise, upwards at a moonrise
+fn assert_crossing(observer: &Observer, event: PassEvent, rising: bool)
+{
+    let elevation = |time: Epoch| Moon::new().observation_at(observer, time).unwrap().look_angles.elevation.degrees();
+    let before = elevation(event.time - Duration::minutes(1));
+    let after = elevation(event.time + Duration::minutes(1));
+
+    assert!((event.elevation.degrees() - MOONRISE_ELEVATION).abs() < 1e-3, "{}", event.elevation.degrees());
+    assert_eq!(after > before, rising, "{}", event.time);
+}
@@ -0,0 +1,262 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Pass prediction
+ *
+ * The passes of the ISS over Paris on 2008 September 20 are checked against a scan of the
+ * elevation every second, and the passes cut by the time window against the full ones. That
+ * evening the ISS is visible from Paris from its rise until it enters the shadow of the
+ * Earth, and from Stockholm from the end of the nautical twilight.
+ */
+
+use chrono::{Duration, TimeZone, Utc};
+use tardis::eclipse::ShadowModel;
+use tardis::passes::{Pass, PassEvent, PassPredictor, RiseSet, Visibility, VisibleSegment};
+use tardis::sun::Twilight;
+use tardis::time::Epoch;
+use tardis::tle::TLE;
+use tardis::traits::Observable;
+use tardis::utils::{Coordinates, Observer};
+
+const ISS: [&str; 3] = [
+    "ISS (ZARYA)",
+    "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+    "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+];
+
+fn iss() -> TLE
+{
+    TLE::from_lines(ISS[1].as_bytes(), ISS[2].as_bytes(), ISS[0].as_bytes()).unwrap()
+}
+
+fn paris() -> Observer
+{
+    Observer::new(Coordinates::with_altitude(48.8566, 2.3522, 0.035))
+}
+
+fn stockholm() -> Observer
+{
+    Observer::new(Coordinates::new(59.3, 18.1))
+}
+
+fn at(hour: u32, min: u32, sec: u32) -> Epoch
+{
+    Epoch::from_datetime(Utc.with_ymd_and_hms(2008, 9, 20, hour, min, sec).unwrap())
+}
+
+fn start() -> Epoch
+{
+    Epoch::from_datetime(Utc.with_ymd_and_hms(2008, 9, 20, 12, 0, 0).unwrap())
+}
+
+fn end() -> Epoch
+{
+    start() + Duration::hours(12)
+}
+
+fn seconds(duration: Duration) -> f64
+{
+    duration.num_nanoseconds().unwrap() as f64 * 1e-9
+}
+
+/// Rise, culmination and set times [s from the start] and maximum elevation [°] of the passes
+/// found by sampling the elevation every second
+fn scan(satellite: &TLE, observer: &Observer, start: Epoch, end: Epoch) -> Vec<(f64, f64, f64, f64)>
+{
+    let elevation = |t: i64| satellite.observation_at(observer, start + Duration::seconds(t)).unwrap().look_angles.elevation.degrees();
+    let mut passes = Vec::new();
+    let mut pass: Option<(f64, f64, f64)> = None;
+    let mut previous = elevation(0);
+
+    for t in 1..=(end - start).num_seconds() {
+        let e = elevation(t);
+
+        // Crossings interpolated between the samples
+        if previous < 0.0 && e >= 0.0 {
+            pass = Some((t as f64 - e / (e - previous), t as f64, e));
+        } else if let Some((rise, top, max)) = pass.as_mut() {
+            if e > *max {
+                *top = t as f64;
+                *max = e;
+            }
+            if e < 0.0 {
+                passes.push((*rise, *top, t as f64 - e / (e - previous), *max));
+                pass = None;
+            }
+        }
+        previous = e;
+    }
+
+    passes
+}
+
+#[test]
+fn passes_against_a_scan()
+{
+    let satellite = iss();
+    let observer = paris();
+    let expected = scan(&satellite, &observer, start(), end());
+    let passes = PassPredictor::new(&satellite, &observer).passes(start(), end()).unwrap();
+
+    assert!(expected.len() >= 3, "{:?}", expected);
+    assert_eq!(passes.len(), expected.len());
+
+    for (pass, (rise, top, set, max)) in passes.iter().zip(expected) {
+        let rise_time = seconds(pass.rise.unwrap().time - start());
+        let set_time = seconds(pass.set.unwrap().time - start());
+        let top_time = seconds(pass.culmination.time - start());
+
+        // The interpolation of the scan is good to about 10 ms at the horizon
+        assert!((rise_time - rise).abs() < 0.1, "{} != {}", rise_time, rise);
+        assert!((set_time - set).abs() < 0.1, "{} != {}", set_time, set);
+        assert!(pass.rise.unwrap().elevation.degrees().abs() < 1e-3);
+        assert!(pass.set.unwrap().elevation.degrees().abs() < 1e-3);
+
+        // The elevation is flat at the culmination: the scan is at most 0.5 s off
+        assert!((top_time - top).abs() < 1.0, "{} != {}", top_time, top);
+        assert!(pass.max_elevation().degrees() >= max - 1e-6, "{} < {}", pass.max_elevation().degrees(), max);
+        assert!(pass.max_elevation().degrees() - max < 1e-2, "{} != {}", pass.max_elevation().degrees(), max);
+        assert!(rise_time < top_time && top_time < set_time);
+    }
+}
+
+#[test]
+fn passes_cut_by_the_window()
+{
+    let satellite = iss();
+    let observer = paris();
+    let predictor = PassPredictor::new(&satellite, &observer);
+    let pass = predictor.passes(start(), end()).unwrap()[0];
+    let culmination = pass.culmination.time;
+
+    // Pass in progress at the beginning of the window
+    let passes = predictor.passes(culmination, end()).unwrap();
+    assert!(passes[0].rise.is_none());
+    assert_same_event_times(passes[0].set, pass.set);
+    assert!((seconds(passes[0].culmination.time - culmination)).abs() < 0.1);
+
+    // Pass in progress at the end of the window
+    let passes = predictor.passes(start(), culmination).unwrap();
+    let last: &Pass = passes.last().unwrap();
+    assert!(last.set.is_none());
+    assert_same_event_times(last.rise, pass.rise);
+    assert!((seconds(last.culmination.time - culmination)).abs() < 0.1);
+
+    match predictor.rise_set(culmination, end()).unwrap() {
+        RiseSet::Crossings { rise, set } => {
+            assert!(rise.is_some(), "the next pass rises");
+            assert_same_event_times(set, predictor.passes(culmination, end()).unwrap().last().unwrap().set);
+        },
+        _ => panic!("crossings expected"),
+    }
+}
+
+#[test]
+fn always_above_and_below()
+{
+    let satellite = iss();
+    let observer = paris();
+    let predictor = PassPredictor::new(&satellite, &observer);
+    let passes = predictor.passes(start(), end()).unwrap();
+    let first = passes[0];
+    let second = passes[1];
+
+    // Inside a pass
+    let rise = first.rise.unwrap().time + Duration::seconds(10);
+    let set = first.set.unwrap().time - Duration::seconds(10);
+    assert!(matches!(predictor.rise_set(rise, set).unwrap(), RiseSet::AlwaysAbove));
+
+    // Between two passes
+    let after = first.set.unwrap().time + Duration::seconds(10);
+    let before = second.rise.unwrap().time - Duration::seconds(10);
+    assert!(matches!(predictor.rise_set(after, before).unwrap(), RiseSet::AlwaysBelow));
+
+    // Above an elevation that the ISS never reaches from Paris
+    let predictor = PassPredictor::new(&satellite, &observer).with_min_elevation(89.0);
+    assert!(matches!(predictor.rise_set(start(), end()).unwrap(), RiseSet::AlwaysBelow));
+}
+
+#[test]
+fn visibility()
+{
+    let satellite = iss();
+    let predictor = PassPredictor::new(&satellite, &paris());
+
+    // Pass from 18:17 to 18:25 just after the sunset, then from 19:51 to 20:01
+    assert_eq!(predictor.visibility(at(18, 21, 0)).unwrap(), Visibility::Daylight);
+    assert_eq!(predictor.visibility(at(19, 53, 0)).unwrap(), Visibility::Visible);
+    assert_eq!(predictor.visibility(at(20, 0, 0)).unwrap(), Visibility::Eclipsed);
+    assert_eq!(predictor.visibility(at(21, 0, 0)).unwrap(), Visibility::BelowHorizon);
+
+    // The culmination is at 53°
+    let predictor = PassPredictor::new(&satellite, &paris()).with_min_elevation(60.0);
+    assert_eq!(predictor.visibility(at(19, 53, 0)).unwrap(), Visibility::BelowHorizon);
+
+    // The civil twilight ends during the first pass, after the ISS entered the shadow
+    let predictor = PassPredictor::new(&satellite, &paris()).with_darkness(Twilight::Civil);
+    assert_eq!(predictor.visibility(at(18, 21, 0)).unwrap(), Visibility::Daylight);
+    assert_eq!(predictor.visibility(at(18, 25, 0)).unwrap(), Visibility::Eclipsed);
+}
+
+#[test]
+fn visible_passes()
+{
+    let satellite = iss();
+
+    // Visible from the rise until the ISS enters the shadow of the Earth
+    let predictor = PassPredictor::new(&satellite, &paris());
+    let visible = predictor.visible_passes(start(), end()).unwrap();
+    assert_eq!(visible.len(), 1);
+    assert_eq!(visible[0].segments.len(), 1);
+
+    let pass = visible[0].pass;
+    let segment = visible[0].segments[0];
+    assert!(seconds(pass.rise.unwrap().time - at(19, 51, 29)).abs() < 1.0, "{}", pass.rise.unwrap().time);
+    assert_same_event_times(Some(segment.start), pass.rise);
+    assert_eq!(segment.before, Visibility::BelowHorizon);
+    assert_eq!(segment.after, Visibility::Eclipsed);
+    assert!(segment.end.time < pass.set.unwrap().time);
+    assert_segment(&predictor, &segment);
+
+    // Visible in the penumbra, which the cylindrical shadow ignores
+    let cylindrical = PassPredictor::new(&satellite, &paris()).with_shadow_model(ShadowModel::Cylindrical);
+    let shadow = cylindrical.visible_passes(start(), end()).unwrap()[0].segments[0].end.time;
+    assert!(shadow < segment.end.time && seconds(segment.end.time - shadow) < 10.0, "{} {}", shadow, segment.end.time);
+
+    // Visible from the end of the nautical twilight until the ISS enters the shadow
+    let predictor = PassPredictor::new(&satellite, &stockholm());
+    let visible = predictor.visible_passes(start(), end()).unwrap();
+    assert_eq!(visible.len(), 1);
+
+    let pass = visible[0].pass;
+    let segment = visible[0].segments[0];
+    assert_eq!(segment.before, Visibility::Daylight);
+    assert_eq!(segment.after, Visibility::Eclipsed);
+    assert!(pass.rise.unwrap().time < segment.start.time && segment.end.time < pass.set.unwrap().time);
+    assert_segment(&predictor, &segment);
+
+    // The passes of the night are in the shadow
+    let predictor = PassPredictor::new(&satellite, &paris());
+    assert!(predictor.visible_passes(at(21, 0, 0), end()).unwrap().is_empty());
+}
+
+/// The object is visible during the segment and for the given reasons out of it
+fn assert_segment(predictor: &PassPredictor, segment: &VisibleSegment)
+{
+    let start = segment.start.time;
+    let end = segment.end.time;
+
+    assert_eq!(predictor.visibility(start - Duration::seconds(1)).unwrap(), segment.before);
+    assert_eq!(predictor.visibility(start + Duration::milliseconds(100)).unwrap(), Visibility::Visible);
+    assert_eq!(predictor.visibility(start + (end - start) / 2).unwrap(), Visibility::Visible);
+    assert_eq!(predictor.visibility(end - Duration::milliseconds(100)).unwrap(), Visibility::Visible);
+    assert_eq!(predictor.visibility(end + Duration::seconds(1)).unwrap(), segment.after);
+}
+
+fn assert_same_event_times(a: Option<PassEvent>, b: Option<PassEvent>)
+{
+    let (a, b) = (a.unwrap(), b.unwrap());
+    assert!(seconds(a.time - b.time).abs() < 0.05, "{} != {}", a.time, b.time);
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* JPL SPK ephemerides
+ *
+ * Small synthetic kernels with Chebyshev coefficients evaluated by hand:
+ *   T0 = 1, T1 = s, T2 = 2s² - 1
+ *   T0' = 0, T1' = 1, T2' = 4s
+ * with s = (t - middle) / radius, and the velocity of type 2 segments dP/ds / radius.
+ */
+
+mod common;
+
+use common::Segment;
+use tardis::spk::{SpkFile, EARTH, EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, SUN};
+use tardis::time::{Epoch, JD_J2000, TimeScale};
+use tardis::Error;
+
+/// Obliquity of ECLIPJ2000
+const OBLIQUITY: f64 = 84381.448 / 3600.0;
+
+/// Earth-Moon barycenter: type 2 in J2000, two intervals of 200 s from -100 s
+fn barycenter() -> Segment
+{
+    Segment {
+        target: EARTH_MOON_BARYCENTER,
+        center: SOLAR_SYSTEM_BARYCENTER,
+        frame: 1,
+        data_type: 2,
+        init: -100.0,
+        interval_length: 200.0,
+        records: vec![
+            vec![0.0, 100.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, -1.0, 0.5, 0.25],
+            vec![200.0, 100.0, 10.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        ],
+    }
+}
+
+/// Earth relatively to the Earth-Moon barycenter: type 3 in ECLIPJ2000, one interval of
+/// 400 s from -100 s
+fn earth() -> Segment
+{
+    Segment {
+        target: EARTH,
+        center: EARTH_MOON_BARYCENTER,
+        frame: 17,
+        data_type: 3,
+        init: -100.0,
+        interval_length: 400.0,
+        records: vec![
+            vec![100.0, 200.0, 100.0, 10.0, 50.0, 0.0, 0.0, 0.0, 0.1, 0.0, 0.2, 0.0, 0.0, 0.0],
+        ],
+    }
+}
+
+/// Epoch at the given TDB seconds since J2000
+fn epoch(seconds: f64) -> Epoch
+{
+    Epoch::from_julian_day_parts(JD_J2000, seconds / 86400.0, TimeScale::TDB)
+}
+
+fn assert_close(actual: [f64; 3], expected: [f64; 3])
+{
+    for i in 0..3 {
+        assert!((actual[i] - expected[i]).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn type_2()
+{
+    let kernel = SpkFile::parse(common::kernel(&[barycenter()])).unwrap();
+    assert_eq!(kernel.bodies(), vec![EARTH_MOON_BARYCENTER]);
+
+    // s = 0.5: T = [1, 0.5, -0.5], T' = [0, 1, 2]
+    let (p, v) = kernel.state(EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, &epoch(50.0)).unwrap();
+    assert_close(p, [0.5, 3.5, -0.875]);
+    assert_close(v, [0.08, 0.17, 0.01]);
+
+    // Second interval, s = 0.5
+    let (p, v) = kernel.state(EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, &epoch(250.0)).unwrap();
+    assert_close(p, [10.0, 0.0, 0.0]);
+    assert_close(v, [0.03, 0.0, 0.0]);
+
+    // Opposite state
+    let (p, v) = kernel.state(SOLAR_SYSTEM_BARYCENTER, EARTH_MOON_BARYCENTER, &epoch(50.0)).unwrap();
+    assert_close(p, [-0.5, -3.5, 0.875]);
+    assert_close(v, [-0.08, -0.17, -0.01]);
+}
+
+#[test]
+fn type_3_in_ecliptic()
+{
+    let kernel = SpkFile::parse(common::kernel(&[barycenter(), earth()])).unwrap();
+    assert_eq!(kernel.bodies(), vec![EARTH_MOON_BARYCENTER, EARTH]);
+
+    // s = -0.25: T = [1, -0.25]. (97.5, 50, 0) and (0.1, 0.2, 0) in the ecliptic.
+    let (sin, cos) = OBLIQUITY.to_radians().sin_cos();
+    let (p, v) = kernel.state(EARTH, EARTH_MOON_BARYCENTER, &epoch(50.0)).unwrap();
+    assert_close(p, [97.5, 50.0 * cos, 50.0 * sin]);
+    assert_close(v, [0.1, 0.2 * cos, 0.2 * sin]);
+
+    // Chain to the solar system barycenter
+    let (p, v) = kernel.state(EARTH, SOLAR_SYSTEM_BARYCENTER, &epoch(50.0)).unwrap();
+    assert_close(p, [98.0, 3.5 + 50.0 * cos, -0.875 + 50.0 * sin]);
+    assert_close(v, [0.18, 0.17 + 0.2 * cos, 0.01 + 0.2 * sin]);
+}
+
+#[test]
+fn unavailable()
+{
+    let kernel = SpkFile::parse(common::kernel(&[barycenter()])).unwrap();
+
+    assert!(matches!(kernel.state(EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, &epoch(400.0)), Err(Error::Ephemeris(_))));
+    assert!(matches!(kernel.state(SUN, SOLAR_SYSTEM_BARYCENTER, &epoch(50.0)), Err(Error::Ephemeris(_))));
+    assert!(matches!(kernel.body(SUN), Err(Error::Ephemeris(_))));
+
+    // Galactic frame
+    let mut segment = barycenter();
+    segment.frame = 13;
+    let kernel = SpkFile::parse(common::kernel(&[segment])).unwrap();
+    assert!(matches!(kernel.state(EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, &epoch(50.0)), Err(Error::Ephemeris(_))));
+}
+
+#[test]
+fn corrupted()
+{
+    let valid = common::kernel(&[barycenter()]);
+    let summary = 1024 + 3 * 8;
+
+    // Negative, out of file and looping summary records
+    for fward in [-1, i32::MIN, 1000] {
+        let mut data = valid.clone();
+        common::put_int(&mut data, 76, fward);
+        assert!(matches!(SpkFile::parse(data), Err(Error::Parse(_))), "FWARD = {}", fward);
+    }
+    let mut data = valid.clone();
+    common::put_double(&mut data, 1024, 2.0);
+    assert!(matches!(SpkFile::parse(data), Err(Error::Parse(_))));
+
+    let mut data = valid.clone();
+    common::put_double(&mut data, 1024 + 16, 1e18);
+    assert!(matches!(SpkFile::parse(data), Err(Error::Parse(_))));
+
+    // Addresses of the segment
+    for (address, end_address) in [(0, 10), (-5, 10), (10, 2), (385, 1), (385, i32::MAX)] {
+        let mut data = valid.clone();
+        common::put_int(&mut data, summary + 16 + 16, address);
+        common::put_int(&mut data, summary + 16 + 20, end_address);
+        assert!(matches!(SpkFile::parse(data), Err(Error::Parse(_))), "{} {}", address, end_address);
+    }
+
+    // Directory inconsistent with the addresses
+    let mut data = valid.clone();
+    let directory = data.len() - 1024 + (2 * 11 + 2) * 8;
+    common::put_double(&mut data, directory, 1e300);
+    assert!(matches!(SpkFile::parse(data), Err(Error::Parse(_))));
+
+    assert!(matches!(SpkFile::parse(valid[..1024].to_vec()), Err(Error::Parse(_))));
+}
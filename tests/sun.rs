@@ -0,0 +1,101 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Sunrise, sunset and twilights
+ *
+ * Paris on the summer solstice of 2024 (sunrise at 03:47 UTC and sunset at 19:58 UTC), where
+ * the Sun stays above -18°, and Tromsø, north of the polar circle, during the polar day and
+ * the polar night.
+ */
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use tardis::passes::{PassEvent, RiseSet};
+use tardis::sun::{sun_times, Twilight};
+use tardis::utils::{Coordinates, Observer};
+
+fn paris() -> Observer
+{
+    Observer::new(Coordinates::with_altitude(48.8566, 2.3522, 0.035))
+}
+
+fn tromso() -> Observer
+{
+    Observer::new(Coordinates::new(69.6492, 18.9553))
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate
+{
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc>
+{
+    Utc.with_ymd_and_hms(year, month, day, hour, min, 0).unwrap()
+}
+
+/// Rise and set of the crossings
+fn crossings(rise_set: RiseSet) -> (PassEvent, PassEvent)
+{
+    match rise_set {
+        RiseSet::Crossings { rise: Some(rise), set: Some(set) } => (rise, set),
+        _ => panic!("rise and set expected"),
+    }
+}
+
+/// The event is within a minute of the given time
+fn assert_near(event: PassEvent, expected: DateTime<Utc>)
+{
+    let difference = (event.time.to_datetime() - expected).num_seconds();
+    assert!(difference.abs() <= 60, "{} != {}", event.time, expected);
+}
+
+#[test]
+fn paris_at_the_summer_solstice()
+{
+    let times = sun_times(&paris(), date(2024, 6, 21)).unwrap();
+
+    let (sunrise, sunset) = crossings(times.sunrise_sunset);
+    assert_near(sunrise, utc(2024, 6, 21, 3, 47));
+    assert_near(sunset, utc(2024, 6, 21, 19, 58));
+
+    // Solar noon at 11:52 UTC, at 90° - 48.86° + 23.44°
+    assert_near(times.transit, utc(2024, 6, 21, 11, 52));
+    assert!((times.transit.elevation.degrees() - 64.58).abs() < 0.05, "{}", times.transit.elevation.degrees());
+
+    // The twilights are nested around the day
+    let (civil_dawn, civil_dusk) = crossings(times.civil);
+    let (nautical_dawn, nautical_dusk) = crossings(times.twilight(Twilight::Nautical));
+    assert!(nautical_dawn.time < civil_dawn.time && civil_dawn.time < sunrise.time);
+    assert!(sunset.time < civil_dusk.time && civil_dusk.time < nautical_dusk.time);
+
+    // The Sun stays above -18° at midnight: the astronomical twilight lasts all night
+    assert!(matches!(times.astronomical, RiseSet::AlwaysAbove));
+}
+
+#[test]
+fn tromso_polar_day()
+{
+    let times = sun_times(&tromso(), date(2024, 6, 21)).unwrap();
+
+    assert!(matches!(times.sunrise_sunset, RiseSet::AlwaysAbove));
+    for twilight in [Twilight::Civil, Twilight::Nautical, Twilight::Astronomical] {
+        assert!(matches!(times.twilight(twilight), RiseSet::AlwaysAbove));
+    }
+    assert!(times.transit.elevation.degrees() > 43.0);
+}
+
+#[test]
+fn tromso_polar_night()
+{
+    let times = sun_times(&tromso(), date(2024, 12, 21)).unwrap();
+
+    // The Sun culminates 3° below the horizon: civil twilight at noon
+    assert!(matches!(times.sunrise_sunset, RiseSet::AlwaysBelow));
+    assert!((times.transit.elevation.degrees() + 3.1).abs() < 0.2, "{}", times.transit.elevation.degrees());
+
+    let (dawn, dusk) = crossings(times.civil);
+    assert!(dawn.time < times.transit.time && times.transit.time < dusk.time);
+    assert!(matches!(times.nautical, RiseSet::Crossings { .. }));
+}
@@ -0,0 +1,108 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Time scales
+ *
+ * The offsets between the scales are checked against their definitions and published values:
+ * TT - UTC and GPS - UTC from the leap seconds, UT1 - UTC from an EOP entry and TDB - TT
+ * against the test of eraDtdb (ERFA), which includes the topocentric terms of a few µs.
+ */
+
+use chrono::{Duration, TimeZone, Utc};
+use tardis::eop::{set_earth_orientation, EarthOrientation, EopFormat};
+use tardis::time::{Epoch, JD_J2000, TimeScale};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// A nanosecond [day]
+const NANOSECOND: f64 = 1e-9 / SECONDS_PER_DAY;
+
+/// Scales that do not depend on the EOP table installed by `universal_time`
+const SCALES: [TimeScale; 5] = [TimeScale::UTC, TimeScale::TAI, TimeScale::TT, TimeScale::GPS, TimeScale::TDB];
+
+/// First entry of finals2000A.all (1973-01-02): UT1 - UTC = 0.8084178 s
+const FINALS_2000A: &str = "73 1 2 41684.00 I  0.120733 0.009786  0.136966 0.015902  I 0.8084178 0.0002710  0.0000 0.1916  P    -0.766    0.199    -0.720    0.300   .143000   .137000   .8075000    -0.766    -0.720  ";
+
+/// Difference between two scales at the epoch [s]
+fn offset(epoch: &Epoch, scale: TimeScale, reference: TimeScale) -> f64
+{
+    let (day, fraction) = epoch.julian_day_parts(scale);
+    let (reference_day, reference_fraction) = epoch.julian_day_parts(reference);
+
+    ((day - reference_day) + (fraction - reference_fraction)) * SECONDS_PER_DAY
+}
+
+#[test]
+fn atomic_scales()
+{
+    // TAI - UTC = 32 s
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap());
+    assert!((offset(&epoch, TimeScale::TAI, TimeScale::UTC) - 32.0).abs() < 1e-6);
+    assert!((offset(&epoch, TimeScale::TT, TimeScale::UTC) - 64.184).abs() < 1e-6);
+    assert!((offset(&epoch, TimeScale::GPS, TimeScale::UTC) - 13.0).abs() < 1e-6);
+
+    // TAI - UTC = 37 s
+    let epoch = Epoch::from_datetime(Utc.with_ymd_and_hms(2017, 1, 1, 0, 0, 0).unwrap());
+    assert!((offset(&epoch, TimeScale::TT, TimeScale::UTC) - 69.184).abs() < 1e-6);
+    assert!((offset(&epoch, TimeScale::GPS, TimeScale::UTC) - 18.0).abs() < 1e-6);
+
+    // J2000 is 2000 January 1 at 12h TT
+    let epoch = Epoch::from_julian_day(JD_J2000, TimeScale::TT);
+    let expected = Utc.with_ymd_and_hms(2000, 1, 1, 11, 58, 55).unwrap() + Duration::microseconds(816000);
+    assert!((epoch.to_datetime() - expected).num_nanoseconds().unwrap().abs() < 1000, "{}", epoch);
+}
+
+#[test]
+fn barycentric_dynamical_time()
+{
+    // eraDtdb(2448939.5, 0.123, ...) = -0.1280368005936998991e-2 s
+    let epoch = Epoch::from_julian_day_parts(2448939.5, 0.123, TimeScale::TT);
+    let tdb_tt = offset(&epoch, TimeScale::TDB, TimeScale::TT);
+    assert!((tdb_tt + 0.001280368).abs() < 30e-6, "{}", tdb_tt);
+
+    // Annual term of 1.657 ms
+    for days in (0..365).step_by(5) {
+        let epoch = Epoch::from_julian_day(JD_J2000 + days as f64, TimeScale::TT);
+        assert!(offset(&epoch, TimeScale::TDB, TimeScale::TT).abs() < 1.7e-3);
+    }
+}
+
+#[test]
+fn universal_time()
+{
+    set_earth_orientation(EarthOrientation::parse(FINALS_2000A, EopFormat::Finals2000A).unwrap());
+
+    let epoch = Epoch::from_julian_day(2441684.5, TimeScale::UTC);
+    assert!((offset(&epoch, TimeScale::UT1, TimeScale::UTC) - 0.8084178).abs() < 1e-6);
+
+    let (day, fraction) = epoch.julian_day_parts(TimeScale::UT1);
+    let back = Epoch::from_julian_day_parts(day, fraction, TimeScale::UT1);
+    assert!(((back - epoch).num_nanoseconds().unwrap()).abs() <= 1);
+}
+
+#[test]
+fn julian_day_parts_round_trip()
+{
+    for scale in SCALES {
+        for (day, fraction) in [(2451544.5, 0.0), (2460000.5, 0.123456789012), (2460000.5, 1.0 - 1e-12), (2455000.0, 0.25)] {
+            let epoch = Epoch::from_julian_day_parts(day, fraction, scale);
+            let (d, f) = epoch.julian_day_parts(scale);
+
+            assert!(((d - day) + (f - fraction)).abs() < NANOSECOND, "{} {} {}: {} {}", scale, day, fraction, d, f);
+            assert!((0.0..1.0).contains(&f));
+        }
+    }
+}
+
+#[test]
+fn nanoseconds()
+{
+    let date = Utc.with_ymd_and_hms(2024, 6, 21, 3, 47, 12).unwrap() + Duration::nanoseconds(123456789);
+    let epoch = Epoch::from_datetime(date);
+
+    assert_eq!(epoch.to_datetime(), date);
+    assert_eq!((epoch + Duration::nanoseconds(1)) - epoch, Duration::nanoseconds(1));
+    assert_eq!((epoch + Duration::nanoseconds(1)).to_datetime(), date + Duration::nanoseconds(1));
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Two-line elements
+ *
+ * Malformed lines are reported as parse errors instead of panicking.
+ */
+
+use tardis::tle::TLE;
+use tardis::traits::Observable;
+use tardis::Error;
+
+const ISS: [&str; 3] = [
+    "ISS (ZARYA)",
+    "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+    "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+];
+
+fn parse(line1: &str, line2: &str) -> tardis::Result<TLE>
+{
+    TLE::from_lines(line1.as_bytes(), line2.as_bytes(), ISS[0].as_bytes())
+}
+
+#[test]
+fn valid()
+{
+    let tle = parse(ISS[1], ISS[2]).unwrap();
+    assert_eq!(tle.name(), "ISS (ZARYA)");
+}
+
+#[test]
+fn malformed()
+{
+    // Bad checksum
+    assert!(matches!(parse(&ISS[1].replace("2927", "2928"), ISS[2]), Err(Error::Parse(_))));
+
+    // Truncated lines
+    assert!(matches!(parse(&ISS[1][..60], ISS[2]), Err(Error::Parse(_))));
+    assert!(matches!(parse(ISS[1], ""), Err(Error::Parse(_))));
+
+    // Line numbers that are not digits, with a valid checksum
+    assert!(matches!(parse(&ISS[1].replacen('1', "A", 1).replace("2927", "2926"), ISS[2]), Err(Error::Parse(_))));
+    assert!(matches!(parse(ISS[1], &ISS[2].replacen('2', "B", 1).replace("63537", "63535")), Err(Error::Parse(_))));
+}
@@ -0,0 +1,44 @@
+/*
+ * Copyright Contributors to the tardis project
+ * SPDX-License-Identifier: LGPL-2.1-or-later
+ */
+
+/* Points and vectors with the frame as a type parameter
+ *
+ * Elements of the same frame type at different epochs are only combined after a conversion.
+ */
+
+use tardis::frames::TEME;
+use tardis::geometry::{FramedPoint, FramedVector};
+use tardis::time::{Epoch, TimeScale};
+
+#[test]
+fn same_epoch()
+{
+    let epoch = Epoch::from_julian_day(2459000.5, TimeScale::UTC);
+    let p = FramedPoint::new(7000.0, 0.0, 0.0, TEME::new(epoch));
+    let v = FramedVector::new(0.0, 10.0, 0.0, TEME::new(epoch));
+
+    assert_eq!((p + v).coordinates(), [7000.0, 10.0, 0.0]);
+}
+
+#[test]
+fn converted_to_the_same_epoch()
+{
+    let epoch = Epoch::from_julian_day(2459000.5, TimeScale::UTC);
+    let later = Epoch::from_julian_day(2459000.75, TimeScale::UTC);
+    let p = FramedPoint::new(7000.0, 0.0, 0.0, TEME::new(epoch));
+    let q = FramedPoint::new(7000.0, 0.0, 0.0, TEME::new(later)).to_frame(TEME::new(epoch)).unwrap();
+
+    assert!((p - q).length() < 1.0);
+}
+
+#[test]
+#[should_panic(expected = "different epochs")]
+fn different_epochs()
+{
+    let p = FramedPoint::new(7000.0, 0.0, 0.0, TEME::new(Epoch::from_julian_day(2459000.5, TimeScale::UTC)));
+    let v = FramedVector::new(0.0, 10.0, 0.0, TEME::new(Epoch::from_julian_day(2459000.75, TimeScale::UTC)));
+
+    let _ = p + v;
+}